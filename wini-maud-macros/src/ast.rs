@@ -1,5 +1,5 @@
 use {
-    proc_macro2::{TokenStream, TokenTree},
+    proc_macro2::{Ident, TokenStream, TokenTree},
     proc_macro_error::SpanRange,
     syn::Lit,
 };
@@ -34,6 +34,11 @@ pub enum Markup {
     /// A function returning Markup and having some files linked to it
     Component {
         expr: TokenStream,
+        /// The named arguments, such as `[card(title = "Hello")]`. The arguments are then not in
+        /// `expr`.
+        props: Option<Vec<ComponentProp>>,
+        /// The block nested in the component, such as `[card(title = "Hello") { p { "Body" } }]`
+        children: Option<Children>,
         outer_span: SpanRange,
     },
     Special {
@@ -205,6 +210,27 @@ impl Toggler {
     }
 }
 
+#[derive(Debug)]
+pub struct ComponentProp {
+    pub name: Ident,
+    pub value: TokenStream,
+}
+
+/// The markup nested in a component, passed to it as arguments
+#[derive(Debug)]
+pub struct Children {
+    /// Everything but the slots, passed as `children`. `None` if there are only slots.
+    pub block: Option<Block>,
+    pub slots: Vec<Slot>,
+}
+
+/// A named slot of a component: `@slot footer { ... }`, passed as `footer`
+#[derive(Debug)]
+pub struct Slot {
+    pub name: Ident,
+    pub body: Block,
+}
+
 #[derive(Debug)]
 pub struct MatchArm {
     pub head: TokenStream,
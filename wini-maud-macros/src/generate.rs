@@ -9,22 +9,26 @@ pub fn generate(
     markups: Vec<Markup>,
     output_ident: TokenTree,
     linked_files: TokenTree,
+    scope: Option<String>,
 ) -> TokenStream {
     let mut build = Builder::new(output_ident.clone());
-    Generator::new(output_ident, linked_files).markups(markups, &mut build);
+    Generator::new(output_ident, linked_files, scope).markups(markups, &mut build);
     build.finish()
 }
 
 struct Generator {
     output_ident: TokenTree,
     linked_files: TokenTree,
+    /// The attributes added to every element, with `@scope`
+    scope: Option<String>,
 }
 
 impl Generator {
-    fn new(output_ident: TokenTree, linked_files: TokenTree) -> Generator {
+    fn new(output_ident: TokenTree, linked_files: TokenTree, scope: Option<String>) -> Generator {
         Generator {
             output_ident,
             linked_files,
+            scope,
         }
     }
 
@@ -63,7 +67,12 @@ impl Generator {
             Markup::Literal { content, .. } => build.push_escaped(&content),
             Markup::Symbol { symbol } => self.name(symbol, build),
             Markup::Splice { expr, .. } => self.splice(expr, build),
-            Markup::Component { expr, .. } => self.component(expr, build),
+            Markup::Component {
+                expr,
+                props,
+                children,
+                ..
+            } => self.component(expr, props, children, build),
             Markup::Element { name, attrs, body } => self.element(name, attrs, body, build),
             Markup::Let { tokens, .. } => build.push_tokens(tokens),
             Markup::Special { segments } => {
@@ -93,7 +102,13 @@ impl Generator {
         }
     }
 
-    fn component(&self, mut expr: TokenStream, build: &mut Builder) {
+    fn component(
+        &self,
+        mut expr: TokenStream,
+        props: Option<Vec<ComponentProp>>,
+        children: Option<Children>,
+        build: &mut Builder,
+    ) {
         use quote::ToTokens;
         let output_ident = self.output_ident.clone();
         let linked_files = self.linked_files.clone();
@@ -144,7 +159,19 @@ impl Generator {
                 },
             }
         };
-        let called_expr = if should_we_call_expr {
+        let children = children.map(|children| self.children(children));
+        let called_expr = if let Some(mut props) = props {
+            // A slot can be an optional prop
+            props.extend(children.into_iter().flatten().map(|ComponentProp { name, value }| {
+                ComponentProp {
+                    name,
+                    value: quote!(::maud::props::IntoSlot::into_slot(#value)),
+                }
+            }));
+            Self::component_with_props(expr, props)
+        } else if let Some(children) = children.and_then(|children| children.into_iter().next()) {
+            Self::component_with_children(expr, children.value, should_we_call_expr)
+        } else if should_we_call_expr {
             quote!(#expr)
         } else {
             quote!(#expr())
@@ -172,6 +199,89 @@ impl Generator {
         ));
     }
 
+    /// `card(title = "Hello")` => `card(::maud::props::builder(&card).title("Hello").build())`,
+    /// with the builder of the props generated by the `#[component(props)]` of `card`
+    fn component_with_props(expr: TokenStream, props: Vec<ComponentProp>) -> TokenStream {
+        let setters = props
+            .into_iter()
+            .map(|ComponentProp { name, value }| quote!(.#name(#value)));
+
+        quote!(#expr(::maud::props::builder(&#expr) #(#setters)* .build()))
+    }
+
+    /// `card("Hello")` => `card("Hello", children)`, and `card` => `card(children)`
+    fn component_with_children(
+        expr: TokenStream,
+        children: TokenStream,
+        is_called: bool,
+    ) -> TokenStream {
+        if !is_called {
+            return quote!(#expr(#children));
+        }
+
+        let mut tokens = expr.into_iter().collect::<Vec<_>>();
+        let Some(TokenTree::Group(args)) = tokens.pop() else {
+            unreachable!("a called component ends with its arguments");
+        };
+        let mut args_with_children = args.stream();
+        let has_trailing_comma = matches!(
+            args_with_children.clone().into_iter().last(),
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == ','
+        );
+        if !args_with_children.is_empty() && !has_trailing_comma {
+            args_with_children.extend(quote!(,));
+        }
+        args_with_children.extend(children);
+
+        let mut group = Group::new(args.delimiter(), args_with_children);
+        group.set_span(args.span());
+        tokens.push(TokenTree::Group(group));
+        tokens.into_iter().collect()
+    }
+
+    /// The children of a component, then its slots, rendered as `Markup`s.
+    ///
+    /// Their linked files are added to the ones of the markup containing the component, since the
+    /// component renders them without its own.
+    fn children(&self, Children { block, slots }: Children) -> Vec<ComponentProp> {
+        let children = block.map(|block| {
+            ComponentProp {
+                name: Ident::new("children", block.outer_span.first),
+                value: self.nested_markup(block),
+            }
+        });
+        let slots = slots.into_iter().map(|Slot { name, body }| {
+            ComponentProp {
+                name,
+                value: self.nested_markup(body),
+            }
+        });
+
+        children.into_iter().chain(slots).collect()
+    }
+
+    /// A block rendered in its own `Markup`, with its linked files added to this one
+    fn nested_markup(&self, block: Block) -> TokenStream {
+        let output_ident = TokenTree::Ident(Ident::new("__maud_nested_output", Span::mixed_site()));
+        let mut build = Builder::new(output_ident.clone());
+        Generator::new(
+            output_ident.clone(),
+            self.linked_files.clone(),
+            self.scope.clone(),
+        )
+            .markups(block.markups, &mut build);
+        let stmts = build.finish();
+
+        quote!({
+            let mut #output_ident = alloc::string::String::new();
+            #stmts
+            maud::Markup {
+                content: maud::PreEscaped(#output_ident),
+                linked_files: maud::macro_private::HashSet::new(),
+            }
+        })
+    }
+
     fn block(
         &self,
         Block {
@@ -198,6 +308,10 @@ impl Generator {
     fn element(&self, name: TokenStream, attrs: Vec<Attr>, body: ElementBody, build: &mut Builder) {
         build.push_str("<");
         self.name(name.clone(), build);
+        if let Some(scope) = &self.scope {
+            build.push_str(" ");
+            build.push_str(scope);
+        }
         self.attrs(attrs, build);
         build.push_str(">");
         if let ElementBody::Block { block } = body {
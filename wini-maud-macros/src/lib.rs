@@ -13,7 +13,7 @@ mod parse;
 
 use {
     proc_macro2::{Ident, Span, TokenStream, TokenTree},
-    proc_macro_error::proc_macro_error,
+    proc_macro_error::{abort_call_site, proc_macro_error},
     quote::quote,
 };
 
@@ -24,13 +24,14 @@ pub fn html(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 }
 
 fn expand(input: TokenStream) -> TokenStream {
+    let (scope, input) = scope(input);
     let output_ident = TokenTree::Ident(Ident::new("__maud_output", Span::mixed_site()));
     let linked_files = TokenTree::Ident(Ident::new("__maud_linked_files", Span::mixed_site()));
     // Heuristic: the size of the resulting markup tends to correlate with the
     // code size of the template itself
     let size_hint = input.to_string().len();
     let markups = parse::parse(input);
-    let stmts = generate::generate(markups, output_ident.clone(), linked_files.clone());
+    let stmts = generate::generate(markups, output_ident.clone(), linked_files.clone(), scope);
     quote!({
         extern crate alloc;
         extern crate maud;
@@ -44,3 +45,27 @@ fn expand(input: TokenStream) -> TokenStream {
         }
     })
 }
+
+/// `@scope "data-a data-b";` at the start of `html!` adds these attributes to every element it
+/// writes, but not to the ones of the components it renders:
+/// `html! { @scope "data-a"; p { [child] } }` => `<p data-a>...</p>`.
+///
+/// Added by `#[component]` for the style sheets scoped to its directory.
+fn scope(input: TokenStream) -> (Option<String>, TokenStream) {
+    let mut tokens = input.clone().into_iter();
+
+    match (tokens.next(), tokens.next(), tokens.next(), tokens.next()) {
+        (
+            Some(TokenTree::Punct(at)),
+            Some(TokenTree::Ident(keyword)),
+            Some(TokenTree::Literal(attributes)),
+            Some(TokenTree::Punct(semicolon)),
+        ) if at.as_char() == '@' && keyword == "scope" && semicolon.as_char() == ';' => {
+            match syn::Lit::new(attributes) {
+                syn::Lit::Str(attributes) => (Some(attributes.value()), tokens.collect()),
+                _ => abort_call_site!("expected the attributes of `@scope` as a string"),
+            }
+        },
+        _ => (None, input),
+    }
+}
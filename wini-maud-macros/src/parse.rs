@@ -119,6 +119,13 @@ impl Parser {
                                 };
                                 abort!(span, "`@let` only works inside a block");
                             },
+                            "slot" => {
+                                let span = SpanRange {
+                                    first: at_span,
+                                    last: ident.span(),
+                                };
+                                abort!(span, "`@slot` only works directly inside a component");
+                            },
                             other => {
                                 let span = SpanRange {
                                     first: at_span,
@@ -175,10 +182,7 @@ impl Parser {
             // Component
             TokenTree::Group(ref group) if group.delimiter() == Delimiter::Bracket => {
                 self.advance();
-                ast::Markup::Component {
-                    expr: group.stream(),
-                    outer_span: SpanRange::single_span(group.span()),
-                }
+                self.component(group.stream(), SpanRange::single_span(group.span()))
             },
             // Splice
             TokenTree::Group(ref group) if group.delimiter() == Delimiter::Parenthesis => {
@@ -201,6 +205,116 @@ impl Parser {
         markup
     }
 
+    /// Parses a component: `[name]`, `[name(arg1, arg2)]` or `[name(prop1 = value1)]`, optionally
+    /// followed by `?` or `!`, and by its children: `[name(prop1 = value1) { ... }]`.
+    fn component(&mut self, expr: TokenStream, outer_span: SpanRange) -> ast::Markup {
+        let mut tokens = expr.into_iter().collect::<Vec<_>>();
+        let children = match tokens.last() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                let children = self.children(group.stream(), SpanRange::single_span(group.span()));
+                tokens.pop();
+                Some(children)
+            },
+            _ => None,
+        };
+
+        let suffix = match tokens.last() {
+            Some(TokenTree::Punct(punct)) if matches!(punct.as_char(), '?' | '!') => tokens.pop(),
+            _ => None,
+        };
+
+        let mut props = match tokens.last() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                component_props(group.stream())
+            },
+            _ => None,
+        };
+        if props.is_some() {
+            tokens.pop();
+        }
+
+        // The slots are named, so they are passed as props
+        if props.is_none() && children.as_ref().is_some_and(|children| !children.slots.is_empty()) {
+            if let Some(TokenTree::Group(group)) = tokens.last() &&
+                group.delimiter() == Delimiter::Parenthesis
+            {
+                if !group.stream().is_empty() {
+                    abort!(
+                        group,
+                        "the slots can't be passed with positional arguments";
+                        help = "name the arguments of a `#[component(props)]`: `(name = value)`"
+                    );
+                }
+                tokens.pop();
+            }
+            props = Some(Vec::new());
+        }
+
+        ast::Markup::Component {
+            expr: tokens.into_iter().chain(suffix).collect(),
+            props,
+            children,
+            outer_span,
+        }
+    }
+
+    /// Parses the children of a component, and its slots: `@slot name { ... }`.
+    fn children(&mut self, body: TokenStream, outer_span: SpanRange) -> ast::Children {
+        let tokens = body.into_iter().collect::<Vec<_>>();
+        let mut rest = Vec::with_capacity(tokens.len());
+        let mut slots = Vec::<ast::Slot>::new();
+        let mut idx = 0;
+        loop {
+            match &tokens[idx..] {
+                [] => break,
+                [TokenTree::Punct(at), TokenTree::Ident(keyword), ..]
+                    if at.as_char() == '@' && *keyword == "slot" =>
+                {
+                    let (name, body) = match &tokens[idx + 2..] {
+                        [TokenTree::Ident(name), TokenTree::Group(body), ..]
+                            if body.delimiter() == Delimiter::Brace =>
+                        {
+                            (name, body)
+                        },
+                        _ => {
+                            let span = SpanRange {
+                                first: at.span(),
+                                last: keyword.span(),
+                            };
+                            abort!(span, "expected a name and a body for this `@slot`");
+                        },
+                    };
+                    if *name == "children" {
+                        abort!(
+                            name,
+                            "`children` is the markup outside of the slots";
+                            help = "put it directly in the component"
+                        );
+                    }
+                    if slots.iter().any(|slot| slot.name == *name) {
+                        abort!(name, "the slot `{}` is already defined", name);
+                    }
+
+                    slots.push(ast::Slot {
+                        name: name.clone(),
+                        body: self.block(body.stream(), SpanRange::single_span(body.span())),
+                    });
+                    idx += 4;
+                },
+                [token, ..] => {
+                    rest.push(token.clone());
+                    idx += 1;
+                },
+            }
+        }
+
+        // A component with only slots doesn't get any `children`
+        let block = (!rest.is_empty() || slots.is_empty())
+            .then(|| self.block(rest.into_iter().collect(), outer_span));
+
+        ast::Children { block, slots }
+    }
+
     /// Parses a literal string.
     ///
     /// If `allow_int_literal` is `true`, then integer literals (like `123`) will be accepted and
@@ -783,3 +897,45 @@ impl Parser {
         }
     }
 }
+
+/// The named arguments of a component, or `None` if its arguments are positional.
+///
+/// An argument is named if it starts with `name =`. A comma followed by something else is part of
+/// the value of the previous argument, such as the one in `HashMap::<u8, u8>::new()`.
+fn component_props(args: TokenStream) -> Option<Vec<ast::ComponentProp>> {
+    let tokens = args.into_iter().collect::<Vec<_>>();
+    let prop_name = |idx: usize| match (tokens.get(idx), tokens.get(idx + 1)) {
+        (Some(TokenTree::Ident(name)), Some(TokenTree::Punct(eq)))
+            if eq.as_char() == '=' && eq.spacing() == Spacing::Alone =>
+        {
+            Some(name.clone())
+        },
+        _ => None,
+    };
+
+    let mut props = Vec::<ast::ComponentProp>::new();
+    let mut idx = 0;
+    while idx < tokens.len() {
+        let start = match tokens[idx] {
+            _ if idx == 0 => Some(idx),
+            TokenTree::Punct(ref punct) if punct.as_char() == ',' => Some(idx + 1),
+            _ => None,
+        };
+
+        if let Some(start) = start &&
+            let Some(name) = prop_name(start)
+        {
+            props.push(ast::ComponentProp {
+                name,
+                value: TokenStream::new(),
+            });
+            idx = start + 2;
+            continue;
+        }
+
+        props.last_mut()?.value.extend_one(tokens[idx].clone());
+        idx += 1;
+    }
+
+    (!props.is_empty()).then_some(props)
+}
@@ -21,6 +21,7 @@ use {
 };
 
 mod escape;
+pub mod props;
 
 /// An adapter that escapes HTML special characters.
 ///
@@ -0,0 +1,81 @@
+//! The props of the components called with named arguments: `[card(title = "Hello")]`.
+//!
+//! The component takes a single struct of props, built with the builder of [`Props`]:
+//!
+//! ```rust,ignore
+//! card(maud::props::builder(&card).title("Hello").build())
+//! ```
+//!
+//! The children of the component are passed as the prop `children`, and each `@slot name { ... }`
+//! as the prop `name`. These props are `Markup`, or `Option<Markup>` if they are optional.
+//!
+//! Each prop of the builder is `()` until it's set, and `(T,)` after, so that a missing prop
+//! without a default, or a prop that doesn't exist, is a compile error.
+
+use crate::Markup;
+
+/// A struct of props, with a builder.
+pub trait Props {
+    type Builder;
+
+    fn builder() -> Self::Builder;
+}
+
+/// A component taking the props `P`
+pub trait Component<P> {}
+
+impl<F, P, Fut> Component<P> for F where F: Fn(P) -> Fut {}
+
+/// The builder of the props of a component, inferred from the component itself.
+pub fn builder<P: Props>(_component: &impl Component<P>) -> P::Builder {
+    P::builder()
+}
+
+/// A prop without a default, that must be set before building the props
+#[diagnostic::on_unimplemented(
+    message = "a required prop of the component is missing",
+    label = "the props without a default must be set with `name = value`"
+)]
+pub trait RequiredProp<T> {
+    fn into_prop(self) -> T;
+}
+
+impl<T> RequiredProp<T> for (T,) {
+    fn into_prop(self) -> T {
+        self.0
+    }
+}
+
+/// A prop with a default, that can be omitted
+pub trait OptionalProp<T> {
+    fn into_prop_or_else(self, default: impl FnOnce() -> T) -> T;
+}
+
+impl<T> OptionalProp<T> for () {
+    fn into_prop_or_else(self, default: impl FnOnce() -> T) -> T {
+        default()
+    }
+}
+
+impl<T> OptionalProp<T> for (T,) {
+    fn into_prop_or_else(self, _default: impl FnOnce() -> T) -> T {
+        self.0
+    }
+}
+
+/// The markup of the children or of a slot, passed to a prop
+pub trait IntoSlot<T> {
+    fn into_slot(self) -> T;
+}
+
+impl IntoSlot<Markup> for Markup {
+    fn into_slot(self) -> Markup {
+        self
+    }
+}
+
+impl IntoSlot<Option<Markup>> for Markup {
+    fn into_slot(self) -> Option<Markup> {
+        Some(self)
+    }
+}
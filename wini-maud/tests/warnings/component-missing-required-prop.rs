@@ -0,0 +1,63 @@
+use maud::{
+    html,
+    props::{OptionalProp, Props, RequiredProp},
+    Markup,
+};
+
+// What `#[component(props)]` generates for `card(title: String, #[default] level: u8)`
+struct CardProps {
+    title: String,
+    level: u8,
+}
+
+impl Props for CardProps {
+    type Builder = CardPropsBuilder<(), ()>;
+
+    fn builder() -> Self::Builder {
+        CardPropsBuilder { title: (), level: () }
+    }
+}
+
+struct CardPropsBuilder<Title, Level> {
+    title: Title,
+    level: Level,
+}
+
+impl<Level> CardPropsBuilder<(), Level> {
+    fn title(self, title: String) -> CardPropsBuilder<(String,), Level> {
+        CardPropsBuilder { title: (title,), level: self.level }
+    }
+}
+
+impl<Title> CardPropsBuilder<Title, ()> {
+    fn level(self, level: u8) -> CardPropsBuilder<Title, (u8,)> {
+        CardPropsBuilder { title: self.title, level: (level,) }
+    }
+}
+
+impl<Title, Level> CardPropsBuilder<Title, Level> {
+    fn build(self) -> CardProps
+    where
+        Title: RequiredProp<String>,
+        Level: OptionalProp<u8>,
+    {
+        CardProps {
+            title: self.title.into_prop(),
+            level: self.level.into_prop_or_else(|| 2),
+        }
+    }
+}
+
+async fn card(props: CardProps) -> Markup {
+    html! { h1 { (props.title) (props.level) } }
+}
+
+async fn page() -> Markup {
+    html! {
+        [card(level = 1)]
+    }
+}
+
+fn main() {
+    let _ = page();
+}
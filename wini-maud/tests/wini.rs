@@ -1,6 +1,10 @@
 use {
     hashbrown::HashSet,
-    maud::{html, Markup},
+    maud::{
+        html,
+        props::{OptionalProp, Props, RequiredProp},
+        Markup,
+    },
 };
 
 #[tokio::test]
@@ -82,3 +86,123 @@ async fn component_with_parameters() {
     assert_eq!(result.linked_files.len(), 0);
     assert_eq!(result.into_string(), "<main><span>Hello Amy!</span></main>");
 }
+
+
+async fn wrapper(class: &str, children: Markup) -> Markup {
+    html! { div class=(class) { (children) } }
+}
+
+#[tokio::test]
+async fn component_with_children() {
+    let result = html! { [wrapper("box") { p { [component_with_linked_file] } }] };
+    assert_eq!(result.linked_files.len(), 1);
+    assert_eq!(result.into_string(), r#"<div class="box"><p>test</p></div>"#);
+}
+
+async fn section(children: Markup) -> Markup {
+    html! { section { (children) } }
+}
+
+#[tokio::test]
+async fn nested_components_with_children() {
+    let result = html! { [section { [section { [parent] }] }] };
+    assert_eq!(result.linked_files.len(), 2);
+    assert_eq!(result.into_string(), "<section><section>test</section></section>");
+}
+
+
+// What `#[component(props)]` generates for `modal(children: Markup, #[default] footer: Option<Markup>)`
+struct ModalProps {
+    children: Markup,
+    footer: Option<Markup>,
+}
+
+impl Props for ModalProps {
+    type Builder = ModalPropsBuilder<(), ()>;
+
+    fn builder() -> Self::Builder {
+        ModalPropsBuilder {
+            children: (),
+            footer: (),
+        }
+    }
+}
+
+struct ModalPropsBuilder<Children, Footer> {
+    children: Children,
+    footer: Footer,
+}
+
+impl<Footer> ModalPropsBuilder<(), Footer> {
+    fn children(self, children: Markup) -> ModalPropsBuilder<(Markup,), Footer> {
+        ModalPropsBuilder {
+            children: (children,),
+            footer: self.footer,
+        }
+    }
+}
+
+impl<Children> ModalPropsBuilder<Children, ()> {
+    fn footer(self, footer: Option<Markup>) -> ModalPropsBuilder<Children, (Option<Markup>,)> {
+        ModalPropsBuilder {
+            children: self.children,
+            footer: (footer,),
+        }
+    }
+}
+
+impl<Children, Footer> ModalPropsBuilder<Children, Footer> {
+    fn build(self) -> ModalProps
+    where
+        Children: RequiredProp<Markup>,
+        Footer: OptionalProp<Option<Markup>>,
+    {
+        ModalProps {
+            children: self.children.into_prop(),
+            footer: self.footer.into_prop_or_else(|| None),
+        }
+    }
+}
+
+async fn modal(props: ModalProps) -> Markup {
+    html! {
+        dialog {
+            (props.children)
+            @if let Some(footer) = props.footer {
+                footer { (footer) }
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn component_with_slots() {
+    let result = html! {
+        [modal {
+            p { "Body" }
+            @slot footer { [child] }
+        }]
+    };
+    assert_eq!(result.linked_files.len(), 1);
+    assert_eq!(
+        result.into_string(),
+        "<dialog><p>Body</p><footer>test</footer></dialog>"
+    );
+}
+
+#[tokio::test]
+async fn scoped_elements() {
+    let result = html! {
+        @scope "data-a data-b";
+        main {
+            [section { p { "Mine" } }]
+            [param_component("Amy")]
+            br;
+        }
+    };
+    // Not on the elements of the components
+    assert_eq!(
+        result.into_string(),
+        "<main data-a data-b><section><p data-a data-b>Mine</p></section><span>Hello Amy!</span><br data-a data-b></main>"
+    );
+}
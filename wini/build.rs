@@ -0,0 +1,336 @@
+//! Generate the router of the pages from the directories of `path.pages` (see `./src/router.rs`).
+
+#[path = "src/shared/wini/fs_router.rs"]
+mod fs_router;
+
+use {
+    fs_router::{Segment, module_path, pages_module, route},
+    std::{
+        collections::{BTreeSet, HashSet},
+        env,
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+
+/// A page found in the pages directory
+struct Page {
+    route: String,
+    module: String,
+    /// The modules defining the layouts of the page, from the closest to the farthest
+    layouts: Vec<String>,
+    is_dynamic: bool,
+    has_action: bool,
+    has_ssg_params: bool,
+}
+
+/// The functions of a `mod.rs` used by the router
+#[derive(Default)]
+pub(crate) struct PageModule {
+    /// `pub async fn render`
+    pub(crate) has_page: bool,
+    /// `pub async fn layout`, applied to all the pages in its directory
+    pub(crate) has_layout: bool,
+    /// `pub async fn action`, handling the forms posted to the page
+    pub(crate) has_action: bool,
+    /// `pub fn ssg_params`, the parameters of the page to render statically
+    pub(crate) has_ssg_params: bool,
+    /// The `path` of the `#[page(path = "...")]` of the file, that can be anywhere in the sources
+    pub(crate) paths: Vec<String>,
+}
+
+impl PageModule {
+    /// The functions defined at the root of a file, if it exists
+    pub(crate) fn parse(file: &Path) -> Self {
+        let Ok(source) = fs::read_to_string(file) else {
+            return Self::default();
+        };
+
+        // The error is reported by the compiler, with the rest of the module
+        let Ok(file_ast) = syn::parse_file(&source) else {
+            println!(
+                "cargo::warning=Couldn't parse `{}`: its pages aren't routed",
+                file.display()
+            );
+            return Self::default();
+        };
+
+        let mut module = Self::default();
+        page_paths(&file_ast.items, &mut module.paths);
+
+        for item in file_ast.items {
+            let syn::Item::Fn(function) = item else {
+                continue;
+            };
+            if !matches!(function.vis, syn::Visibility::Public(_)) {
+                continue;
+            }
+
+            let is_async = function.sig.asyncness.is_some();
+            match function.sig.ident.to_string().as_str() {
+                "render" if is_async => module.has_page = true,
+                "layout" if is_async => module.has_layout = true,
+                "action" if is_async => module.has_action = true,
+                "ssg_params" => module.has_ssg_params = true,
+                _ => {},
+            }
+        }
+
+        module
+    }
+}
+
+/// Add the `path` of the `#[page(path = "...")]` of `items`, and of their modules, to `paths`
+fn page_paths(items: &[syn::Item], paths: &mut Vec<String>) {
+    for item in items {
+        let function = match item {
+            syn::Item::Fn(function) => function,
+            syn::Item::Mod(syn::ItemMod {
+                content: Some((_, items)),
+                ..
+            }) => {
+                page_paths(items, paths);
+                continue;
+            },
+            _ => continue,
+        };
+
+        let page_attributes = function.attrs.iter().filter(|attr| {
+            attr.path()
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "page")
+        });
+        for attr in page_attributes {
+            let Ok(args) = attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+            ) else {
+                continue;
+            };
+
+            paths.extend(args.into_iter().filter_map(|arg| {
+                let syn::Meta::NameValue(arg) = arg else {
+                    return None;
+                };
+                let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(path),
+                    ..
+                }) = arg.value
+                else {
+                    return None;
+                };
+
+                arg.path.is_ident("path").then(|| path.value())
+            }));
+        }
+    }
+}
+
+fn main() {
+    println!("cargo::rerun-if-changed=wini.toml");
+
+    let wini_toml = fs::read_to_string("wini.toml").expect("Couldn't read `./wini.toml`");
+    let wini_toml: toml::Table = toml::from_str(&wini_toml).expect("Invalid `./wini.toml`");
+    let pages_path = wini_toml
+        .get("path")
+        .and_then(|path| path.get("pages"))
+        .and_then(toml::Value::as_str)
+        .unwrap_or("./pages");
+
+    // The `#[page(path = "...")]` can be anywhere in the sources
+    println!("cargo::rerun-if-changed=src");
+
+    let pages_dir = Path::new("src").join(pages_path);
+
+    let mut pages = vec![];
+    collect_pages(
+        &pages_dir,
+        &pages_module(pages_path),
+        &mut vec![],
+        &[],
+        &mut pages,
+    );
+    pages.sort_by(|a, b| a.route.cmp(&b.route));
+
+    let mut routes = HashSet::new();
+    for page in &pages {
+        assert!(
+            routes.insert(&page.route),
+            "Multiple pages for the route `{}`",
+            page.route
+        );
+    }
+
+    let mut code = String::from("// Generated by `./build.rs` from the directories of the pages\n");
+
+    // The state is only needed by the layouts
+    let state = if pages.iter().any(|page| !page.layouts.is_empty()) {
+        "state"
+    } else {
+        "_state"
+    };
+
+    code.push_str(&format!("\n/// The router of all the pages\npub fn pages_router({state}: &crate::shared::app_state::AppState) -> axum::Router<crate::shared::app_state::AppState> {{\n    axum::Router::new()"));
+    for page in &pages {
+        code.push_str(&format!(
+            "\n        .route({:?}, {})",
+            page.route,
+            method_router(page)
+        ));
+    }
+    code.push_str("\n}\n");
+
+    code.push_str(&route_macro(&pages));
+
+    // IFFEAT ssg
+    if env::var_os("CARGO_FEATURE_GENERATE_SSG").is_some() ||
+        env::var_os("CARGO_FEATURE_RUN_WITH_SSR").is_some()
+    {
+        code.push_str(&format!("\n/// The router of all the pages to render statically\npub(crate) fn pages_ssg_router({state}: &crate::shared::app_state::AppState) -> crate::shared::wini::ssg::SsgRouter<'static> {{\n    crate::shared::wini::ssg::SsgRouter::new()"));
+        for page in &pages {
+            let route = if !page.is_dynamic {
+                format!(
+                    "\n        .route({:?}, {})",
+                    page.route,
+                    method_router(page)
+                )
+            } else if page.has_ssg_params {
+                format!(
+                    "\n        .route_with_params({:?}, {}, {}::ssg_params())",
+                    page.route,
+                    method_router(page),
+                    page.module
+                )
+            } else {
+                println!(
+                    "cargo::warning=`{}` isn't rendered statically: `{}` doesn't have a `pub fn ssg_params()`",
+                    page.route, page.module
+                );
+                continue;
+            };
+
+            code.push_str(&route);
+        }
+        code.push_str("\n}\n");
+    }
+    // ENDIF
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("Set by cargo"));
+    fs::write(out_dir.join("routes.rs"), code).expect("Couldn't write the generated routes");
+}
+
+/// Find the pages in `dir` and its sub-directories
+fn collect_pages(
+    dir: &Path,
+    pages_module: &str,
+    dir_names: &mut Vec<String>,
+    parent_layouts: &[String],
+    pages: &mut Vec<Page>,
+) {
+    let page_module = PageModule::parse(&dir.join("mod.rs"));
+    let dir_names_str = dir_names.iter().map(String::as_str).collect::<Vec<_>>();
+    let module = module_path(pages_module, &dir_names_str);
+
+    let mut layouts = parent_layouts.to_vec();
+    if page_module.has_layout {
+        layouts.insert(0, format!("{module}::layout"));
+    }
+
+    if page_module.has_page {
+        pages.push(Page {
+            route: route(&dir_names_str),
+            is_dynamic: dir_names_str
+                .iter()
+                .any(|dir_name| Segment::from_dir_name(dir_name).is_dynamic()),
+            has_action: page_module.has_action,
+            has_ssg_params: page_module.has_ssg_params,
+            layouts: layouts.clone(),
+            module,
+        });
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut sub_dirs = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.join("mod.rs").is_file())
+        .collect::<Vec<_>>();
+    sub_dirs.sort();
+
+    for sub_dir in sub_dirs {
+        let Some(dir_name) = sub_dir.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+
+        dir_names.push(dir_name.to_owned());
+        collect_pages(&sub_dir, pages_module, dir_names, &layouts, pages);
+        dir_names.pop();
+    }
+}
+
+/// `get(page).post(action).layer(from_fn_with_state(state.clone(), closest_layout))...`, the
+/// layouts extracting the state of the application
+fn method_router(page: &Page) -> String {
+    let mut handlers = format!("axum::routing::get({}::render)", page.module);
+    if page.has_action {
+        handlers.push_str(&format!(".post({}::action)", page.module));
+    }
+
+    page.layouts.iter().fold(handlers, |method_router, layout| {
+        format!(
+            "{method_router}.layer(axum::middleware::from_fn_with_state(state.clone(), {layout}))"
+        )
+    })
+}
+
+/// `route!("/blog/{slug}", slug = ...)`, with a rule per route of the pages and of the
+/// `#[page(path = "...")]`, so that an unknown route doesn't compile
+fn route_macro(pages: &[Page]) -> String {
+    let mut routes = pages
+        .iter()
+        .map(|page| page.route.clone())
+        .collect::<BTreeSet<_>>();
+
+    for entry in walkdir::WalkDir::new("src")
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        if entry.path().extension().is_some_and(|ext| ext == "rs") {
+            routes.extend(PageModule::parse(entry.path()).paths);
+        }
+    }
+
+    let mut code = String::from(
+        "\n/// The URL of a page from its route, with the values of its parameters in the same order:\n/// `route!(\"/blog/{slug}\", slug = post.slug)`.\n///\n/// The routes are the ones of the pages directory and of the `#[page(path = \"...\")]`. An unknown\n/// route, or a missing parameter, doesn't compile.\nmacro_rules! route {",
+    );
+    for route in &routes {
+        let params = route
+            .split('/')
+            .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+            .map(|param| param.trim_start_matches('*'))
+            .collect::<Vec<_>>();
+
+        let pattern = params
+            .iter()
+            .enumerate()
+            .map(|(idx, param)| format!(", {param} = $p{idx}:expr"))
+            .collect::<String>();
+        let values = params
+            .iter()
+            .enumerate()
+            .map(|(idx, param)| format!("({param:?}, &$p{idx})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        code.push_str(&format!(
+            "\n    ({route:?}{pattern} $(,)?) => {{\n        $crate::shared::wini::route::url({route:?}, &[{values}], &[])\n    }};"
+        ));
+    }
+    code.push_str("\n}\npub(crate) use route;\n");
+
+    code
+}
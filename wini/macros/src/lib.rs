@@ -17,6 +17,7 @@ pub(crate) mod utils;
 /// # Parameters
 ///
 /// - `js_pkgs` - Array of JavaScript package names to include
+/// - `preload` - Array of files to preload, such as fonts or above-the-fold images
 ///
 /// # Return Types
 ///
@@ -56,6 +57,10 @@ pub(crate) mod utils;
 /// }
 /// ```
 ///
+/// The values of the request needed by many components, such as the current user or the locale,
+/// don't have to be passed through every level: they are read from the context of the request with
+/// `ctx::<CurrentUser>()` (see `shared::wini::context`).
+///
 /// ## Handling of `ServerResult<Markup>`
 /// In some cases, your component might return a `ServerError`. In this case, you will need to use
 /// a special syntax to specify how the error should be handled in the caller of the component.
@@ -194,6 +199,89 @@ pub(crate) mod utils;
 /// }
 /// ```
 ///
+/// ## With props
+///
+/// With `props`, the component is called with named arguments, in any order. The parameters with
+/// `#[default]` or `#[default = value]` can be omitted:
+///
+/// ```rust,ignore
+/// use {maud::{html, Markup}, wini_macros::component};
+///
+/// #[component(props)]
+/// pub async fn card(title: &str, #[default] footer: Option<Markup>, #[default = 2] level: u8) -> Markup {
+///     html! {
+///         article {
+///             h2 { (title) }
+///             @if let Some(footer) = footer {
+///                 footer { (footer) }
+///             }
+///         }
+///     }
+/// }
+///
+/// #[page]
+/// pub async fn render() -> Markup {
+///     html! {
+///         [card(title = "Hello")]
+///         [card(level = 1, title = "Bye")]
+///     }
+/// }
+/// ```
+///
+/// The component then takes a single `CardProps` struct, with a field per parameter. A missing prop
+/// without a default is a compile error.
+///
+/// ## With children and slots
+///
+/// The block following a component is passed as its `children`, and each `@slot name { ... }` in
+/// it as the prop `name`, that can be an `Option<Markup>`:
+///
+/// ```rust,ignore
+/// use {maud::{html, Markup}, wini_macros::component};
+///
+/// #[component(props)]
+/// pub async fn modal(title: &str, children: Markup, #[default] footer: Option<Markup>) -> Markup {
+///     html! {
+///         dialog open {
+///             h2 { (title) }
+///             (children)
+///             @if let Some(footer) = footer {
+///                 footer { (footer) }
+///             }
+///         }
+///     }
+/// }
+///
+/// #[page]
+/// pub async fn render() -> Markup {
+///     html! {
+///         [modal(title = "Delete?") {
+///             p { "This can't be undone." }
+///             @slot footer {
+///                 button { "Delete" }
+///             }
+///         }]
+///     }
+/// }
+/// ```
+///
+/// A component without props gets its children as its last argument: `[section { ... }]` calls
+/// `section(children)`.
+///
+/// ## With scoped CSS
+///
+/// The JavaScript and CSS files of the directory of a component are linked to it. A style sheet
+/// named `scoped.css` (or `*.scoped.css`) only applies to the elements written by the components
+/// of its directory: they add its attribute to every element of their `html!`, but not to the
+/// elements of the components they render, and its selectors are rewritten to require it.
+/// `:global(...)` opts out of it. (`./src/shared/wini/scoped_css.rs`)
+///
+/// ```css
+/// /* ./src/components/button/scoped.css */
+/// .button { color: red; }
+/// :global(.dark) .button { color: pink; }
+/// ```
+///
 /// ## Composing multiple components
 ///
 /// ```rust,ignore
@@ -238,6 +326,7 @@ pub fn component(args: TokenStream, item: TokenStream) -> TokenStream {
 /// - `img` - Open Graph image URL
 /// - `robots` - Robot indexing instructions
 /// - `js_pkgs` - Array of JavaScript package names to include
+/// - `preload` - Array of files to preload, such as fonts or above-the-fold images
 /// - `other_meta` - Array of custom meta tag key-value pairs
 ///
 /// # Layout argument types
@@ -258,6 +347,9 @@ pub fn component(args: TokenStream, item: TokenStream) -> TokenStream {
 ///    - `#[from_response_parts]`
 ///    - `#[from_response_body]`
 ///
+/// The state of the application is extracted with `State<AppState>`, so a layout must be added to
+/// a router with `axum::middleware::from_fn_with_state(state.clone(), layout)`.
+///
 /// # Examples
 ///
 /// ## Basic usage with string content
@@ -444,12 +536,30 @@ pub fn layout(args: TokenStream, item: TokenStream) -> TokenStream {
 /// - `img` - Open Graph image URL
 /// - `robots` - Robot indexing instructions (e.g., "index, follow")
 /// - `js_pkgs` - Array of JavaScript package names to include
+/// - `preload` - Array of files to preload, such as fonts or above-the-fold images
 /// - `other_meta` - Array of custom meta tag key-value pairs
+/// - `path` - The route of the page, such as `"/users/{id}"` (see [Typed routes](#typed-routes))
 ///
 /// # Return Types
 ///
 /// The function can return either `Markup` or `ServerResult<Markup>` for error handling.
 ///
+/// # Typed routes
+///
+/// The arguments of the function are axum extractors, such as `State(state): State<AppState>`,
+/// except:
+/// - the ones named like a parameter of `path`, which are deserialized from the path,
+/// - the ones with a `#[query]` attribute, which are deserialized from the query.
+///
+/// Every parameter of `path` must be an argument of the function, and when the page is the `render`
+/// of a directory of the pages, `path` must be the route of its directory. Otherwise, it's a compile
+/// error.
+///
+/// With a `path`, the macro also generates a struct named after the function (`user_profile` =>
+/// `UserProfileRoute`), with a public field per parameter of the path and of the query. It builds
+/// the URL of the page with `url()` or `Display`, so that a link to a renamed page doesn't compile
+/// anymore.
+///
 /// # Examples
 ///
 /// ## Basic usage
@@ -470,10 +580,17 @@ pub fn layout(args: TokenStream, item: TokenStream) -> TokenStream {
 /// ```rust,ignore
 /// use {maud::{html, Markup}, wini_macros::page};
 ///
-/// #[page]
-/// pub async fn user_profile(user_id: String) -> Markup {
+/// #[page(path = "/users/{user_id}")]
+/// pub async fn user_profile(user_id: u32, #[query] tab: Option<String>) -> Markup {
+///     let next = UserProfileRoute {
+///         user_id: user_id + 1,
+///         tab,
+///     };
+///
 ///     html! {
 ///         h1 { "User: " (user_id) }
+///         // `/users/43?tab=posts`
+///         a href=(next) { "Next user" }
 ///     }
 /// }
 /// ```
@@ -579,6 +696,121 @@ pub fn page(args: TokenStream, item: TokenStream) -> TokenStream {
     macros::wini::page::page(args, item)
 }
 
+/// Handles the forms posted to a page, next to its `#[page]`.
+///
+/// The last argument of the function is the form, deserialized from the body of the request. The
+/// other arguments are axum extractors, like the ones of a page.
+///
+/// The function returns an [`Action`] (or a `ServerResult<Action>`):
+/// - `Action::redirect(...)` to redirect to another page, with a `303 See Other`,
+/// - `Action::Invalid(errors)` to render the page again with the [`FormErrors`], that the page gets
+///   as an extractor. It's also what happens when the form can't be deserialized.
+///
+/// With htmx, the redirections use a `HX-Redirect` header, and the page is rendered again with a
+/// `200 OK` so that htmx swaps it.
+///
+/// In the pages directory, a `pub async fn action` in the `mod.rs` of a page handles the `POST`
+/// requests to its route.
+///
+/// With the CSRF protection of `[security.csrf]`, the token of the session is added to the
+/// `method="post"` forms of the page, also when it's rendered again: the forms don't need a
+/// `[csrf_input(&csrf)]`.
+///
+/// # Parameters
+///
+/// - `page` - The page rendered again when the form is invalid. `render` by default.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use {
+///     crate::shared::wini::action::{Action, FormErrors},
+///     maud::{html, Markup},
+///     wini_macros::{action, page},
+/// };
+///
+/// #[derive(serde::Deserialize)]
+/// pub struct Subscription {
+///     email: String,
+/// }
+///
+/// #[page]
+/// pub async fn render(errors: FormErrors) -> Markup {
+///     html! {
+///         form method="post" {
+///             input name="email" value=[errors.value("email")];
+///             @for error in errors.field("email") {
+///                 p.error { (error) }
+///             }
+///             button { "Subscribe" }
+///         }
+///     }
+/// }
+///
+/// #[action]
+/// pub async fn action(form: Subscription) -> ServerResult<Action> {
+///     if !form.email.contains('@') {
+///         let mut errors = FormErrors::new();
+///         errors.add("email", "This isn't an email address");
+///         return Ok(errors.into());
+///     }
+///
+///     subscribe(&form.email).await?;
+///
+///     Ok(Action::redirect(route!("/thanks")))
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn action(args: TokenStream, item: TokenStream) -> TokenStream {
+    macros::wini::action::action(args, item)
+}
+
+/// Registers an async function as the page rendered for some error status codes.
+///
+/// The error page replaces the body of the error responses that don't already have one (or only a
+/// text one), and is then rendered through the layouts and the template like any other page.
+///
+/// # Parameters
+///
+/// The status codes the page is used for. Without status code, the page is used for all the errors
+/// that don't have their own page.
+///
+/// A status code can only have one page: the others are reported when the server starts.
+///
+/// # Signature
+///
+/// The function receives the status code of the response, and the [`Backtrace`] of the error if it
+/// comes from a `#[page]` that returned an `Err`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use {
+///     crate::shared::wini::err::Backtrace,
+///     hyper::StatusCode,
+///     maud::{html, Markup},
+///     wini_macros::error_page,
+/// };
+///
+/// #[error_page(404, 410)]
+/// pub async fn not_found(_status_code: StatusCode, _backtrace: Option<Backtrace>) -> Markup {
+///     html! {
+///         h1 { "This page doesn't exist!" }
+///     }
+/// }
+///
+/// #[error_page]
+/// pub async fn error(status_code: StatusCode, _backtrace: Option<Backtrace>) -> Markup {
+///     html! {
+///         h1 { "Error " (status_code.as_u16()) }
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn error_page(args: TokenStream, item: TokenStream) -> TokenStream {
+    macros::wini::error_page::error_page(args, item)
+}
+
 #[proc_macro_attribute]
 pub fn init_cache(args: TokenStream, item: TokenStream) -> TokenStream {
     macros::wini::cache::init_cache(args, item)
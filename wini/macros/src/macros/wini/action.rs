@@ -0,0 +1,113 @@
+use {
+    crate::utils::wini::{files::get_current_file_path, result::is_ouput_ty_result},
+    proc_macro::TokenStream,
+    quote::{format_ident, quote},
+    syn::{FnArg, Ident, parse_macro_input},
+};
+
+
+pub fn action(args: TokenStream, item: TokenStream) -> TokenStream {
+    // The page rendered again when the form is invalid
+    let mut page = Ident::new("render", proc_macro2::Span::call_site());
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("page") {
+            page = meta.value()?.parse()?;
+            Ok(())
+        } else {
+            Err(meta.error("Unsupported action parameter"))
+        }
+    });
+    parse_macro_input!(args with attr_parser);
+
+
+    // Modify the name of the original function to a reserved one
+    let mut original_function = parse_macro_input!(item as syn::ItemFn);
+    let original_name = original_function.sig.ident.clone();
+    let new_name = Ident::new(
+        &format!("__reserved_fn_wini_{}", original_name),
+        original_name.span(),
+    );
+    original_function.sig.ident = new_name.clone();
+
+    let current_file_path =
+        get_current_file_path().map_or_else(Default::default, |p| p.to_string_lossy().into_owned());
+
+    // The last argument is the form, the others are extractors
+    let mut types = Vec::with_capacity(original_function.sig.inputs.len());
+    for input in &original_function.sig.inputs {
+        match input {
+            FnArg::Typed(pat_type) => types.push((*pat_type.ty).clone()),
+            FnArg::Receiver(_) => {
+                return syn::Error::new_spanned(input, "self parameters not supported.")
+                    .to_compile_error()
+                    .into();
+            },
+        }
+    }
+    let Some(form_ty) = types.pop() else {
+        return syn::Error::new_spanned(
+            &original_function.sig,
+            "An action must have the form as its last argument",
+        )
+        .to_compile_error()
+        .into();
+    };
+    let extractors = (0..types.len())
+        .map(|idx| format_ident!("__wini_arg_{idx}"))
+        .collect::<Vec<_>>();
+
+    let call_inner_action = if is_ouput_ty_result(&original_function) {
+        quote!(
+            match #new_name(#(#extractors,)* __wini_form).await {
+                Ok(action) => action,
+                Err(mut err) => {
+                    // Before the response, that logs the error with its trace
+                    err.add_trace(
+                        crate::shared::wini::err::Trace {
+                            file_path: #current_file_path,
+                            function_name: stringify!(#original_name),
+                        }
+                    );
+
+                    let mut resp = (&err).into_response();
+
+                    let mut backtrace = crate::shared::wini::err::Backtrace::from(err);
+                    backtrace.error_id = resp.extensions().get::<crate::shared::wini::err::ErrorId>().cloned();
+
+                    resp.extensions_mut().insert(backtrace);
+
+                    return resp
+                }
+            }
+        )
+    } else {
+        quote!(#new_name(#(#extractors,)* __wini_form).await)
+    };
+
+
+    // Generate the output code
+    let expanded = quote! {
+        #[allow(non_snake_case)]
+        #original_function
+
+        #[allow(non_snake_case)]
+        pub async fn #original_name(
+            axum::extract::State(__wini_state): axum::extract::State<crate::shared::app_state::AppState>,
+            __wini_parts: axum::http::request::Parts,
+            #(#extractors: #types,)*
+            __wini_body: axum::body::Bytes,
+        ) -> axum::response::Response<axum::body::Body> {
+            use axum::response::IntoResponse;
+
+            let action = match crate::shared::wini::action::parse_form::<#form_ty>(&__wini_body) {
+                Ok(__wini_form) => crate::shared::wini::action::Action::from(#call_inner_action),
+                Err(errors) => crate::shared::wini::action::Action::Invalid(errors),
+            };
+
+            crate::shared::wini::action::respond(action, __wini_parts, &__wini_body, #page, __wini_state).await
+        }
+    };
+
+    // Convert the generated code back to TokenStream
+    TokenStream::from(expanded)
+}
@@ -26,6 +26,8 @@ pub struct ProcMacroParameters {
     pub other_meta: Option<HashMap<String, String>>,
     /// Add JS packages
     pub js_pkgs: Option<Vec<String>>,
+    /// Files to preload: `<link rel="preload">`
+    pub preload: Option<Vec<String>>,
 }
 
 macro_rules! generate_extension_function {
@@ -196,7 +198,7 @@ impl ProcMacroParameters {
 
                     Ok(())
                 },
-                "js_pkgs" => {
+                "js_pkgs" | "preload" => {
                     let lit_array: ExprArray = meta.value()?.parse()?;
                     let mut vec_elements = Vec::with_capacity(lit_array.elems.len());
                     for elem in lit_array.elems {
@@ -210,6 +212,7 @@ impl ProcMacroParameters {
                     // Assign it to the correct key
                     match ident.to_string().as_str() {
                         "js_pkgs" => self.js_pkgs = Some(vec_elements),
+                        "preload" => self.preload = Some(vec_elements),
                         _ => unreachable!("Already matched."),
                     }
 
@@ -3,8 +3,11 @@ use {
     crate::utils::wini::{
         files::{get_current_file_path, get_js_or_css_files_in_current_dir},
         js_pkgs,
+        preload,
         params_from_itemfn::params_from_itemfn,
+        props::{ComponentProps, props},
         result::is_ouput_ty_result,
+        scoped_css::scope_elements,
     },
     proc_macro::TokenStream,
     quote::quote,
@@ -15,7 +18,15 @@ use {
 pub fn component(args: TokenStream, item: TokenStream) -> TokenStream {
     // Convert the attributes in a struct.
     let mut attributes = ProcMacroParameters::default();
-    let attr_parser = syn::meta::parser(|meta| attributes.parse(meta));
+    let mut with_props = false;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("props") {
+            with_props = true;
+            Ok(())
+        } else {
+            attributes.parse(meta)
+        }
+    });
     parse_macro_input!(args with attr_parser);
 
 
@@ -28,6 +39,15 @@ pub fn component(args: TokenStream, item: TokenStream) -> TokenStream {
     );
     original_function.sig.ident = new_name.clone();
 
+    let props = if with_props {
+        match props(&mut original_function, &original_name) {
+            Ok(props) => Some(props),
+            Err(err) => return err.to_compile_error().into(),
+        }
+    } else {
+        None
+    };
+
     let current_file_path =
         get_current_file_path().map_or_else(Default::default, |p| p.to_string_lossy().into_owned());
 
@@ -54,15 +74,29 @@ pub fn component(args: TokenStream, item: TokenStream) -> TokenStream {
 
     let (arguments, param_names) = params_from_itemfn(&original_function);
 
+    // With props, the component takes them instead of its parameters
+    let (arguments, props_items, destructure_props) = match props {
+        Some(ComponentProps {
+            items,
+            argument,
+            destructure,
+        }) => (argument, items, destructure),
+        None => (quote!(#arguments), quote!(), quote!()),
+    };
+
     let files_in_current_dir = get_js_or_css_files_in_current_dir();
+    scope_elements(&mut original_function, &files_in_current_dir);
 
     let js_pkgs = js_pkgs::handle(attributes.js_pkgs, quote!(html.linked_files), false);
+    let preload = preload::handle(attributes.preload, quote!(html.linked_files), false);
 
     // Generate the output code
     let expanded = quote! {
         #[allow(non_snake_case)]
         #original_function
 
+        #props_items
+
         #[allow(non_snake_case)]
         pub async fn #original_name(#arguments) -> #return_type {
             use {
@@ -72,6 +106,7 @@ pub fn component(args: TokenStream, item: TokenStream) -> TokenStream {
 
             const FILES_IN_CURRENT_DIR: &[&str] = &[#(#files_in_current_dir),*];
 
+            #destructure_props
             let mut html = #new_name(#(#param_names),*).await #maybe_early_return;
 
             let hashset = std::collections::HashSet::<String>::from_iter(
@@ -83,6 +118,7 @@ pub fn component(args: TokenStream, item: TokenStream) -> TokenStream {
             html.linked_files.extend(hashset);
 
             #js_pkgs
+            #preload
 
             #return_data
         }
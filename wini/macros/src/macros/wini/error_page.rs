@@ -0,0 +1,62 @@
+use {
+    proc_macro::TokenStream,
+    quote::quote,
+    syn::{Ident, LitInt, Token, parse::Parser, parse_macro_input, punctuated::Punctuated},
+};
+
+
+pub fn error_page(args: TokenStream, item: TokenStream) -> TokenStream {
+    let status_codes = match Punctuated::<LitInt, Token![,]>::parse_terminated.parse(args) {
+        Ok(status_codes) => status_codes,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let mut registered_for = Vec::with_capacity(status_codes.len());
+    for status_code in status_codes {
+        match status_code.base10_parse::<u16>() {
+            Ok(code @ 400..=599) => registered_for.push(quote!(Some(#code))),
+            _ => {
+                return syn::Error::new(
+                    status_code.span(),
+                    "Error pages can only be registered for status codes between 400 and 599",
+                )
+                .to_compile_error()
+                .into();
+            },
+        }
+    }
+
+    // Without status code, the error page is used for all the errors without their own page
+    if registered_for.is_empty() {
+        registered_for.push(quote!(None));
+    }
+
+    let input = parse_macro_input!(item as syn::ItemFn);
+    let name = &input.sig.ident;
+
+    let ctor_name = Ident::new(&format!("__ctor_register_error_page_{name}"), name.span());
+
+    let expanded = quote! {
+        #input
+
+        // Register the error page on the start of the program
+        #[ctor::ctor]
+        fn #ctor_name() {
+            #(
+                crate::shared::wini::error_page::register(
+                    #registered_for,
+                    crate::shared::wini::error_page::RegisteredErrorPage {
+                        page: |status_code, backtrace| -> crate::shared::wini::error_page::ErrorPageFuture {
+                            Box::pin(#name(status_code, backtrace))
+                        },
+                        name: concat!(module_path!(), "::", stringify!(#name)),
+                        file: file!(),
+                    },
+                );
+            )*
+        }
+    };
+
+    // Convert the generated code back to TokenStream
+    TokenStream::from(expanded)
+}
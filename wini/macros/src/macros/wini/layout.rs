@@ -4,7 +4,9 @@ use {
         utils::wini::{
             files::get_js_or_css_files_in_current_dir,
             js_pkgs,
+            preload,
             result::is_ouput_ty_result,
+            scoped_css::scope_elements,
         },
     },
     proc_macro::TokenStream,
@@ -59,7 +61,7 @@ pub fn layout(args: TokenStream, item: TokenStream) -> TokenStream {
                 let from_request_parts = quote!(
                     {
                         let (mut req_parts, body) = req.into_parts();
-                        let ty = match #path_ty::__from_request_parts(&mut req_parts, &()).await {
+                        let ty = match #path_ty::__from_request_parts(&mut req_parts, &__wini_state).await {
                             Ok(ok) => ok,
                             Err(into_resp) => return Ok(into_resp.into_response()),
                         };
@@ -70,7 +72,7 @@ pub fn layout(args: TokenStream, item: TokenStream) -> TokenStream {
                 // ResponseParts
                 let from_response_parts = quote!(
                     {
-                        let ty = match #path_ty::__from_response_parts(&mut resp_parts, &()).await {
+                        let ty = match #path_ty::__from_response_parts(&mut resp_parts, &__wini_state).await {
                             Ok(ok) => ok,
                             Err(into_resp) => return Ok(into_resp.into_response()),
                         };
@@ -80,7 +82,7 @@ pub fn layout(args: TokenStream, item: TokenStream) -> TokenStream {
                 // ResponseBody
                 let from_response_body = if is_last {
                     quote!(
-                        match #path_ty::__from_response_body(resp_body, &()).await {
+                        match #path_ty::__from_response_body(resp_body, &__wini_state).await {
                             Ok(ok) => ok,
                             Err(into_resp) => return Ok(into_resp.into_response()),
                         }
@@ -335,10 +337,12 @@ pub fn layout(args: TokenStream, item: TokenStream) -> TokenStream {
     }
 
     let files_in_current_dir = get_js_or_css_files_in_current_dir();
+    scope_elements(&mut input, &files_in_current_dir);
     let len_files_in_current_dir = files_in_current_dir.len();
     let meta_extensions = attributes.generate_all_extensions(true);
 
     let js_pkgs = js_pkgs::handle(attributes.js_pkgs, quote!(files), true);
+    let preload = preload::handle(attributes.preload, quote!(files), true);
 
     // Generate the output code
     let expanded = quote! {
@@ -348,6 +352,7 @@ pub fn layout(args: TokenStream, item: TokenStream) -> TokenStream {
 
         #[allow(non_snake_case)]
         pub async fn #name(
+            axum::extract::State(__wini_state): axum::extract::State<crate::shared::app_state::AppState>,
             mut req: axum::extract::Request,
             next: axum::middleware::Next
         ) -> crate::shared::wini::err::ServerResult<axum::response::Response> {
@@ -357,6 +362,11 @@ pub fn layout(args: TokenStream, item: TokenStream) -> TokenStream {
                 std::borrow::Cow,
             };
 
+            // The fragments are sent without their layouts
+            if req.extensions().get::<crate::shared::wini::fragment::Fragment>().is_some() {
+                return Ok(next.run(req).await);
+            }
+
             const FILES_IN_CURRENT_DIR: [Cow<'static, str>; #len_files_in_current_dir] = [#(Cow::Borrowed(#files_in_current_dir)),*];
 
             #(#handling_of_request)*
@@ -373,6 +383,7 @@ pub fn layout(args: TokenStream, item: TokenStream) -> TokenStream {
             files.extend(FILES_IN_CURRENT_DIR);
 
             #js_pkgs
+            #preload
 
             // Modify extensions with meta tags in it
             #meta_extensions
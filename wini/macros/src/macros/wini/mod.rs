@@ -1,5 +1,7 @@
+pub mod action;
 pub mod args;
 pub mod cache;
 pub mod component;
+pub mod error_page;
 pub mod layout;
 pub mod page;
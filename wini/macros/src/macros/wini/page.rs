@@ -3,19 +3,29 @@ use {
     crate::utils::wini::{
         files::{get_current_file_path, get_js_or_css_files_in_current_dir},
         js_pkgs,
-        params_from_itemfn::params_from_itemfn,
+        preload,
         result::is_ouput_ty_result,
+        route::PageParams,
+        scoped_css::scope_elements,
     },
     proc_macro::TokenStream,
     quote::quote,
-    syn::{parse_macro_input, Ident},
+    syn::{parse_macro_input, Ident, LitStr},
 };
 
 
 pub fn page(args: TokenStream, item: TokenStream) -> TokenStream {
     // Convert the attributes in a struct.
     let mut attributes = ProcMacroParameters::default();
-    let attr_parser = syn::meta::parser(|meta| attributes.parse(meta));
+    let mut path = None;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("path") {
+            path = Some(meta.value()?.parse::<LitStr>()?);
+            Ok(())
+        } else {
+            attributes.parse(meta)
+        }
+    });
     parse_macro_input!(args with attr_parser);
 
 
@@ -31,28 +41,39 @@ pub fn page(args: TokenStream, item: TokenStream) -> TokenStream {
     let current_file_path =
         get_current_file_path().map_or_else(Default::default, |p| p.to_string_lossy().into_owned());
 
-    let (arguments, param_names) = params_from_itemfn(&original_function);
+    let params = match PageParams::new(path, &mut original_function) {
+        Ok(params) => params,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let arguments = params.arguments(&original_name);
+    let param_names = params.values();
+    let route_items = params.items(&original_name, &original_function.vis);
 
     let files_in_current_dir = get_js_or_css_files_in_current_dir();
+    scope_elements(&mut original_function, &files_in_current_dir);
     let len_files_in_current_dir = files_in_current_dir.len();
     let meta_headers = attributes.generate_all_extensions(false);
     let js_pkgs = js_pkgs::handle(attributes.js_pkgs, quote!(files), true);
+    let preload = preload::handle(attributes.preload, quote!(files), true);
 
     let call_inner_page = if is_ouput_ty_result(&original_function) {
         quote!(
             match #new_name(#(#param_names),*).await {
                 Ok(resp) => resp,
-                Err(err) => {
-                    let mut resp = (&err).into_response();
-
-                    let mut backtrace = crate::shared::wini::err::Backtrace::from(err);
-                    backtrace.trace.push(
+                Err(mut err) => {
+                    // Before the response, that logs the error with its trace
+                    err.add_trace(
                         crate::shared::wini::err::Trace {
                             file_path: #current_file_path,
                             function_name: stringify!(#original_name),
                         }
                     );
 
+                    let mut resp = (&err).into_response();
+
+                    let mut backtrace = crate::shared::wini::err::Backtrace::from(err);
+                    backtrace.error_id = resp.extensions().get::<crate::shared::wini::err::ErrorId>().cloned();
+
                     resp.extensions_mut().insert(backtrace);
 
                     return resp
@@ -69,6 +90,8 @@ pub fn page(args: TokenStream, item: TokenStream) -> TokenStream {
         #[allow(non_snake_case)]
         #original_function
 
+        #route_items
+
         #[allow(non_snake_case)]
         pub async fn #original_name(#arguments) -> axum::response::Response<axum::body::Body> {
             use {
@@ -91,6 +114,7 @@ pub fn page(args: TokenStream, item: TokenStream) -> TokenStream {
             files.extend(linked_files);
 
             #js_pkgs
+            #preload
 
             // Modify header with meta tags in it
             #meta_headers
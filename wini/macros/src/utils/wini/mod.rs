@@ -2,4 +2,8 @@ pub mod files;
 pub mod js_pkgs;
 pub mod params_from_itemfn;
 pub mod path;
+pub mod preload;
+pub mod props;
 pub mod result;
+pub mod route;
+pub mod scoped_css;
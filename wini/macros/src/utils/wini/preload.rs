@@ -0,0 +1,28 @@
+use {proc_macro2::TokenStream, quote::quote};
+
+pub(crate) fn handle(
+    preload: Option<Vec<String>>,
+    add_to: TokenStream,
+    wrap_in_cow: bool,
+) -> TokenStream {
+    let linked_file = if wrap_in_cow {
+        quote!(Cow::Owned(linked_file))
+    } else {
+        quote!(linked_file)
+    };
+
+    if let Some(preload) = preload {
+        quote!(#(
+            {
+                let linked_file = format!(
+                    "{}{}",
+                    crate::shared::wini::resource_hints::PRELOAD_PREFIX,
+                    #preload,
+                );
+                #add_to.insert(#linked_file);
+            }
+        )*)
+    } else {
+        quote!()
+    }
+}
@@ -0,0 +1,244 @@
+use {
+    proc_macro2::{Span, TokenStream},
+    quote::{format_ident, quote},
+    syn::{
+        Expr,
+        FnArg,
+        Ident,
+        ItemFn,
+        Lifetime,
+        Type,
+        visit_mut::{self, VisitMut},
+    },
+};
+
+
+/// A parameter of a component with `#[component(props)]`
+struct Prop {
+    ident: Ident,
+    ty: Type,
+    /// `#[default]` or `#[default = expr]`
+    default: Option<Expr>,
+}
+
+/// The props of a component with `#[component(props)]`
+pub struct ComponentProps {
+    /// The struct of the props, and its builder
+    pub items: TokenStream,
+    /// The argument of the component, replacing its parameters
+    pub argument: TokenStream,
+    /// The parameters of the component, from the argument
+    pub destructure: TokenStream,
+}
+
+/// The struct of the props of a component, named after it (`user_card` => `UserCardProps`), and
+/// its builder, used by `[user_card(name = value)]`.
+///
+/// Removes the `#[default]` attributes from the parameters of the function.
+pub fn props(function: &mut ItemFn, name: &Ident) -> syn::Result<ComponentProps> {
+    if !function.sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &function.sig.generics,
+            "A component with props can't be generic",
+        ));
+    }
+
+    let lifetime = Lifetime::new("'__wini_props", Span::call_site());
+
+    let mut props = Vec::with_capacity(function.sig.inputs.len());
+    let mut has_references = false;
+    for input in &mut function.sig.inputs {
+        let FnArg::Typed(pat_type) = input else {
+            return Err(syn::Error::new_spanned(input, "self parameters not supported."));
+        };
+
+        let syn::Pat::Ident(pat_ident) = &*pat_type.pat else {
+            return Err(syn::Error::new_spanned(
+                &pat_type.pat,
+                "The props must be named by an identifier",
+            ));
+        };
+
+        let mut default = None;
+        if let Some(idx) = pat_type.attrs.iter().position(|attr| attr.path().is_ident("default")) {
+            let attr = pat_type.attrs.remove(idx);
+            default = Some(match attr.meta {
+                syn::Meta::Path(_) => syn::parse_quote!(::core::default::Default::default()),
+                syn::Meta::NameValue(name_value) => name_value.value,
+                syn::Meta::List(_) => {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "Expected `#[default]` or `#[default = value]`",
+                    ));
+                },
+            });
+        }
+
+        // The references of the props borrow for the lifetime of the struct
+        let mut ty = (*pat_type.ty).clone();
+        let mut elided = ElidedLifetimes {
+            lifetime: &lifetime,
+            found: false,
+        };
+        elided.visit_type_mut(&mut ty);
+        has_references |= elided.found;
+
+        props.push(Prop {
+            ident: pat_ident.ident.clone(),
+            ty,
+            default,
+        });
+    }
+
+    let vis = &function.vis;
+    let pascal_case_name = snake_to_pascal_case(&name.to_string());
+    let props_struct = format_ident!("{pascal_case_name}Props", span = name.span());
+    let builder = format_ident!("{pascal_case_name}PropsBuilder", span = name.span());
+
+    // The lifetime is only needed by the references
+    let (lifetime, lifetime_param, phantom_field, phantom_value) = if has_references {
+        (
+            Some(quote!(#lifetime)),
+            Some(quote!(#lifetime,)),
+            Some(quote!(__wini_lifetime: ::core::marker::PhantomData<&#lifetime ()>,)),
+            Some(quote!(__wini_lifetime: ::core::marker::PhantomData,)),
+        )
+    } else {
+        (None, None, None, None)
+    };
+
+    let idents = props.iter().map(|prop| &prop.ident).collect::<Vec<_>>();
+    let tys = props.iter().map(|prop| &prop.ty).collect::<Vec<_>>();
+    // The state of each prop in the builder: `()` or `(T,)`
+    let states = props
+        .iter()
+        .map(|prop| format_ident!("__{}", prop.ident, span = prop.ident.span()))
+        .collect::<Vec<_>>();
+    let unset = props.iter().map(|_| quote!(()));
+
+    let setters = props.iter().enumerate().map(|(idx, Prop { ident, ty, .. })| {
+        let other_states = states
+            .iter()
+            .enumerate()
+            .filter(|(other_idx, _)| *other_idx != idx)
+            .map(|(_, state)| state);
+        let before = &states[..idx];
+        let after = &states[idx + 1..];
+        let others = idents
+            .iter()
+            .filter(|other| **other != ident)
+            .collect::<Vec<_>>();
+
+        quote!(
+            #[allow(non_camel_case_types)]
+            impl<#lifetime_param #(#other_states),*> #builder<#lifetime_param #(#before,)* (), #(#after),*> {
+                pub fn #ident(self, #ident: #ty) -> #builder<#lifetime_param #(#before,)* (#ty,), #(#after),*> {
+                    #builder {
+                        #ident: (#ident,),
+                        #(#others: self.#others,)*
+                        #phantom_value
+                    }
+                }
+            }
+        )
+    });
+
+    let bounds = props.iter().zip(&states).map(|(Prop { ty, default, .. }, state)| {
+        if default.is_some() {
+            quote!(#state: ::maud::props::OptionalProp<#ty>)
+        } else {
+            quote!(#state: ::maud::props::RequiredProp<#ty>)
+        }
+    });
+    let values = props.iter().map(|Prop { ident, default, .. }| {
+        match default {
+            Some(default) => quote!(self.#ident.into_prop_or_else(|| #default)),
+            None => quote!(self.#ident.into_prop()),
+        }
+    });
+
+    let items = quote!(
+        /// The props of the component, built by calling it with named arguments
+        #vis struct #props_struct<#lifetime> {
+            #(pub #idents: #tys,)*
+        }
+
+        impl<#lifetime> ::maud::props::Props for #props_struct<#lifetime> {
+            type Builder = #builder<#lifetime_param #(#unset),*>;
+
+            fn builder() -> Self::Builder {
+                #builder {
+                    #(#idents: (),)*
+                    #phantom_value
+                }
+            }
+        }
+
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        #vis struct #builder<#lifetime_param #(#states),*> {
+            #(#idents: #states,)*
+            #phantom_field
+        }
+
+        #(#setters)*
+
+        #[allow(non_camel_case_types)]
+        impl<#lifetime_param #(#states),*> #builder<#lifetime_param #(#states),*> {
+            pub fn build(self) -> #props_struct<#lifetime>
+            where
+                #(#bounds,)*
+            {
+                use ::maud::props::{OptionalProp, RequiredProp};
+
+                #props_struct {
+                    #(#idents: #values,)*
+                }
+            }
+        }
+    );
+
+    let argument_lifetime = has_references.then(|| quote!(<'_>));
+
+    Ok(ComponentProps {
+        items,
+        argument: quote!(__wini_props: #props_struct #argument_lifetime),
+        destructure: quote!(let #props_struct { #(#idents),* } = __wini_props;),
+    })
+}
+
+/// `user_card` => `UserCard`
+fn snake_to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Give a lifetime to the elided ones: `&str` => `&'lifetime str`
+struct ElidedLifetimes<'a> {
+    lifetime: &'a Lifetime,
+    found: bool,
+}
+
+impl VisitMut for ElidedLifetimes<'_> {
+    fn visit_type_reference_mut(&mut self, reference: &mut syn::TypeReference) {
+        if reference.lifetime.is_none() {
+            reference.lifetime = Some(self.lifetime.clone());
+            self.found = true;
+        }
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.lifetime.clone();
+            self.found = true;
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use {
+    proc_macro2::TokenStream,
+    quote::{format_ident, quote},
+    std::path::PathBuf,
+    syn::{FnArg, Ident, ItemFn, LitStr, Type, Visibility},
+};
+
+#[allow(dead_code, reason = "Only the route of a directory is needed")]
+#[path = "../../../../src/shared/wini/fs_router.rs"]
+mod fs_router;
+
+
+/// Where the value of a parameter of a page comes from
+enum Source {
+    /// `{name}` in the `path` of the page
+    Path,
+    /// `#[query]`
+    Query,
+    /// Any other extractor of axum
+    Extractor,
+}
+
+struct Param {
+    ident: Ident,
+    ty: Type,
+    source: Source,
+    /// The argument of the function, kept in the handler if it's an extractor
+    arg: FnArg,
+}
+
+/// The parameters of a `#[page]`, with the ones coming from its path and its query
+pub struct PageParams {
+    path: Option<LitStr>,
+    params: Vec<Param>,
+}
+
+impl PageParams {
+    /// Sort the parameters of the page, removing the `#[query]` attributes from the function.
+    ///
+    /// Fails if a parameter of `path` isn't a parameter of the function, or if `path` doesn't match
+    /// the directory of a `render` page.
+    pub fn new(path: Option<LitStr>, function: &mut ItemFn) -> syn::Result<Self> {
+        let path_params = path.as_ref().map(|path| params_of_path(&path.value())).unwrap_or_default();
+
+        let mut params = Vec::with_capacity(function.sig.inputs.len());
+        for (idx, input) in function.sig.inputs.iter_mut().enumerate() {
+            let FnArg::Typed(pat_type) = input else {
+                return Err(syn::Error::new_spanned(input, "self parameters not supported."));
+            };
+
+            // The extractors can be destructured, such as `State(state): State<AppState>`: the
+            // handler binds them to a name, and gives them to the function
+            let (ident, arg) = match &*pat_type.pat {
+                syn::Pat::Ident(pat_ident) => {
+                    (pat_ident.ident.clone(), FnArg::Typed(pat_type.clone()))
+                },
+                _ if !pat_type.attrs.iter().any(|attr| attr.path().is_ident("query")) => {
+                    let ident = format_ident!("__wini_arg_{idx}");
+                    let ty = &pat_type.ty;
+                    (ident.clone(), syn::parse_quote!(#ident: #ty))
+                },
+                pat => return Err(syn::Error::new_spanned(pat, "Unsupported parameter pattern")),
+            };
+
+            let query_attr = pat_type.attrs.iter().position(|attr| attr.path().is_ident("query"));
+            let source = if let Some(idx) = query_attr {
+                pat_type.attrs.remove(idx);
+
+                if path_params.contains(&ident.to_string()) {
+                    return Err(syn::Error::new_spanned(
+                        &ident,
+                        format!("`{ident}` is already a parameter of the path"),
+                    ));
+                }
+
+                Source::Query
+            } else if path_params.contains(&ident.to_string()) {
+                Source::Path
+            } else {
+                Source::Extractor
+            };
+
+            params.push(Param {
+                ident,
+                ty: (*pat_type.ty).clone(),
+                source,
+                arg,
+            });
+        }
+
+        if let Some(path) = &path {
+            for path_param in &path_params {
+                if !params.iter().any(|param| param.ident == path_param) {
+                    return Err(syn::Error::new(
+                        path.span(),
+                        format!("`{path_param}` isn't a parameter of the page"),
+                    ));
+                }
+            }
+
+            // The other pages of the directory are routed by their `path`
+            if function.sig.ident == "render" &&
+                let Some(route) = route_of_current_dir() &&
+                route != path.value()
+            {
+                return Err(syn::Error::new(
+                    path.span(),
+                    format!("The path of the page doesn't match its directory: expected `{route}`"),
+                ));
+            }
+        }
+
+        Ok(Self { path, params })
+    }
+
+    fn of_source(&self, source: fn(&Source) -> bool) -> impl Iterator<Item = &Param> {
+        self.params.iter().filter(move |param| source(&param.source))
+    }
+
+    /// The arguments of the handler of the page
+    pub fn arguments(&self, name: &Ident) -> TokenStream {
+        let path_struct = format_ident!("__reserved_struct_wini_path_{name}");
+        let query_struct = format_ident!("__reserved_struct_wini_query_{name}");
+
+        let path = self
+            .of_source(|source| matches!(source, Source::Path))
+            .next()
+            .map(|_| quote!(axum::extract::Path(__wini_path): axum::extract::Path<#path_struct>,));
+        let query = self
+            .of_source(|source| matches!(source, Source::Query))
+            .next()
+            .map(|_| quote!(axum::extract::Query(__wini_query): axum::extract::Query<#query_struct>,));
+        let extractors = self
+            .of_source(|source| matches!(source, Source::Extractor))
+            .map(|param| &param.arg);
+
+        quote!(#path #query #(#extractors),*)
+    }
+
+    /// The values passed to the function of the page, in the order of its parameters
+    pub fn values(&self) -> Vec<TokenStream> {
+        self.params
+            .iter()
+            .map(|Param { ident, source, .. }| {
+                match source {
+                    Source::Path => quote!(__wini_path.#ident),
+                    Source::Query => quote!(__wini_query.#ident),
+                    Source::Extractor => quote!(#ident),
+                }
+            })
+            .collect()
+    }
+
+    /// The structs deserializing the parameters, and the typed route of the page if it has a
+    /// `path`
+    pub fn items(&self, name: &Ident, vis: &Visibility) -> TokenStream {
+        let path_struct = format_ident!("__reserved_struct_wini_path_{name}");
+        let query_struct = format_ident!("__reserved_struct_wini_query_{name}");
+
+        let (path_idents, path_tys): (Vec<_>, Vec<_>) = self
+            .of_source(|source| matches!(source, Source::Path))
+            .map(|param| (&param.ident, &param.ty))
+            .unzip();
+        let (query_idents, query_tys): (Vec<_>, Vec<_>) = self
+            .of_source(|source| matches!(source, Source::Query))
+            .map(|param| (&param.ident, &param.ty))
+            .unzip();
+
+        let path_struct = (!path_idents.is_empty()).then(|| {
+            quote! {
+                #[doc(hidden)]
+                #[allow(non_camel_case_types)]
+                #[derive(serde::Deserialize)]
+                pub struct #path_struct {
+                    #(#path_idents: #path_tys),*
+                }
+            }
+        });
+        let query_struct = (!query_idents.is_empty()).then(|| {
+            quote! {
+                #[doc(hidden)]
+                #[allow(non_camel_case_types)]
+                #[derive(serde::Deserialize)]
+                pub struct #query_struct {
+                    #(#query_idents: #query_tys),*
+                }
+            }
+        });
+        let extracted = quote!(#path_struct #query_struct);
+
+        let Some(path) = &self.path else {
+            return extracted;
+        };
+
+        let route_struct = format_ident!("{}Route", to_pascal_case(&name.to_string()));
+        let doc = format!(" The route of [`{name}`]: `{}`", path.value());
+        let fields = if path_idents.is_empty() && query_idents.is_empty() {
+            quote!(;)
+        } else {
+            quote!({
+                #(pub #path_idents: #path_tys,)*
+                #(pub #query_idents: #query_tys,)*
+            })
+        };
+
+        quote! {
+            #extracted
+
+            #[doc = #doc]
+            #vis struct #route_struct #fields
+
+            impl #route_struct {
+                pub const PATH: &'static str = #path;
+
+                /// The URL of the page, with its query
+                pub fn url(&self) -> String {
+                    crate::shared::wini::route::url(
+                        Self::PATH,
+                        &[#((stringify!(#path_idents), &self.#path_idents)),*],
+                        &[#(crate::shared::wini::route::query_pair(stringify!(#query_idents), &self.#query_idents)),*],
+                    )
+                }
+            }
+
+            impl std::fmt::Display for #route_struct {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str(&self.url())
+                }
+            }
+        }
+    }
+}
+
+
+/// The names of the parameters of a path: `/users/{id}/{*rest}` => `["id", "rest"]`
+fn params_of_path(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| name.trim_start_matches('*').to_owned())
+        .collect()
+}
+
+/// The route of the page calling the macro, if it's a `mod.rs` in the pages directory
+fn route_of_current_dir() -> Option<String> {
+    let file = super::files::get_current_file_path()?.canonicalize().ok()?;
+    if file.file_name()? != "mod.rs" {
+        return None;
+    }
+
+    let manifest_dir = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR")?);
+    let wini_toml = std::fs::read_to_string(manifest_dir.join("wini.toml")).ok()?;
+    let wini_toml = toml::from_str::<toml::Table>(&wini_toml).ok()?;
+    let pages_path = wini_toml
+        .get("path")
+        .and_then(|path| path.get("pages"))
+        .and_then(toml::Value::as_str)
+        .unwrap_or("./pages");
+
+    let pages_dir = manifest_dir.join("src").join(pages_path).canonicalize().ok()?;
+    let dir_names = file
+        .parent()?
+        .strip_prefix(&pages_dir)
+        .ok()?
+        .iter()
+        .map(|dir_name| dir_name.to_str())
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(fs_router::route(&dir_names))
+}
+
+fn to_pascal_case(snake_case: &str) -> String {
+    snake_case
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
@@ -0,0 +1,49 @@
+use {
+    quote::quote,
+    shared::{is_scoped, scope_attribute},
+    syn::{
+        ItemFn,
+        LitStr,
+        Macro,
+        visit_mut::{self, VisitMut},
+    },
+};
+
+/// The attributes are the ones of the style sheets rewritten by the server
+#[path = "../../../../src/shared/wini/scoped_css.rs"]
+#[allow(dead_code)]
+mod shared;
+
+/// Add the attributes of the scoped style sheets among `files` to the elements written by the
+/// `html!`s of `function`, with `@scope`
+pub(crate) fn scope_elements(function: &mut ItemFn, files: &[String]) {
+    let attributes = files
+        .iter()
+        .filter(|file| is_scoped(file))
+        .map(|file| scope_attribute(file))
+        .collect::<Vec<_>>();
+
+    if !attributes.is_empty() {
+        ScopedHtml {
+            attributes: attributes.join(" "),
+        }
+        .visit_block_mut(&mut function.block);
+    }
+}
+
+/// `html! { ... }` => `html! { @scope "data-wini-..."; ... }`
+struct ScopedHtml {
+    attributes: String,
+}
+
+impl VisitMut for ScopedHtml {
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        if mac.path.segments.last().is_some_and(|segment| segment.ident == "html") {
+            let attributes = LitStr::new(&self.attributes, proc_macro2::Span::call_site());
+            let tokens = &mac.tokens;
+
+            mac.tokens = quote!(@scope #attributes; #tokens);
+        }
+        visit_mut::visit_macro_mut(self, mac);
+    }
+}
@@ -23,6 +23,8 @@ declare global {
         t?: string,
         c?: HTMLElement[],
     ) => HTMLElement;
+    // The CSRF token of the page, if the CSRF protection is enabled
+    const csrfToken: () => string | undefined;
     // biome-ignore lint/suspicious/noExplicitAny: the data returned can be of any type.
     const api: (method: string, url: string, data?: { [keyof: string]: string }) => any;
     // biome-ignore lint/suspicious/noExplicitAny: the data returned can be of any type.
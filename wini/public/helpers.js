@@ -43,8 +43,16 @@ var $new = (k, p, t, c) => {
     if (c) for (let s of c) e.appendChild(s);
     return e;
 }
+// The CSRF token of the page, sent with the requests that can change something
+var csrfToken = () => document.querySelector('meta[name="csrf-token"]')?.content;
+document.addEventListener('htmx:configRequest', e => {
+    const token = csrfToken();
+    if (token) e.detail.headers['X-CSRF-Token'] = token;
+});
 var api = (method, url, data) => {
     const options = { method, headers: { 'Content-Type': 'application/json' } };
+    const token = csrfToken();
+    if (token) options.headers['X-CSRF-Token'] = token;
     if (data) options.body = JSON.stringify(data);
     return fetch(url, options).then(res => res.json());
 };
@@ -53,3 +61,136 @@ var post = (url, data) => api('POST', url, data);
 var put = (url, data) => api('PUT', url, data);
 var patch = (url, data) => api('PATCH', url, data);
 var del = (url) => api('DELETE', url);
+
+// Navigation between the pages without reloading them, enabled with `[navigation]` in `wini.toml`.
+// The `<body>` is replaced, and only the new elements of the `<head>` are added: the elements of the
+// template are identified by `data-wini-key`, and the scripts with `data-wini-rerun` run after each
+// navigation. Links with `data-wini-reload` are followed normally.
+// With `prefetch`, the pages are fetched when their links are hovered or focused: the links that
+// change something when they're followed, such as `/logout`, need `data-wini-reload`.
+(() => {
+    const root = document.documentElement;
+    if (!root.hasAttribute('data-wini-navigation')) return;
+
+    const pages = new Map();
+    const withoutHash = url => url.split('#')[0];
+    let current = withoutHash(location.href);
+
+    const isPageLink = a => a instanceof HTMLAnchorElement
+        && a.origin === location.origin
+        && (!a.target || a.target === '_self')
+        && !a.hasAttribute('download')
+        && !a.hasAttribute('data-wini-reload')
+        && !(withoutHash(a.href) === current && a.hash);
+
+    const fetchPage = url => {
+        if (!pages.has(url)) {
+            const page = fetch(url).then(async res => {
+                if (!res.headers.get('content-type')?.startsWith('text/html')) throw res;
+                // The URL after the redirections, such as the one of an action
+                return { url: res.url, html: await res.text() };
+            });
+            page.catch(() => pages.delete(url));
+            pages.set(url, page);
+            // A prefetched page is only used for a short time
+            setTimeout(() => pages.delete(url), 30000);
+        }
+        return pages.get(url);
+    };
+
+    // The elements parsed with `DOMParser` are inert: the scripts must be created again to run. They
+    // also use the nonce of the current page, because it's the one allowed by its CSP.
+    const nonce = document.querySelector('[nonce]')?.nonce;
+    const revive = e => {
+        const fresh = e.tagName === 'SCRIPT' ? document.createElement('script') : document.importNode(e, true);
+        if (e.tagName === 'SCRIPT') {
+            for (const a of e.attributes) fresh.setAttribute(a.name, a.value);
+            fresh.textContent = e.textContent;
+            fresh.async = false;
+        }
+        if (nonce && e.hasAttribute('nonce')) fresh.nonce = nonce;
+        return fresh;
+    };
+
+    const navigate = async (url, push) => {
+        let page;
+        try {
+            page = await fetchPage(withoutHash(url));
+        } catch {
+            location.href = url;
+            return;
+        }
+        pages.delete(withoutHash(url));
+        url = page.url + new URL(url).hash;
+
+        const next = new DOMParser().parseFromString(page.html, 'text/html');
+        if (!next.documentElement.hasAttribute('data-wini-navigation')) {
+            location.href = url;
+            return;
+        }
+
+        const previous = new Map();
+        for (const e of document.head.querySelectorAll('[data-wini-key]')) previous.set(e.dataset.winiKey, e);
+
+        // The style sheets are loaded before replacing the body, so that it's never unstyled
+        const loading = [];
+        const scripts = [];
+        const stale = [...document.head.children].filter(e => !e.hasAttribute('data-wini-key'));
+        for (const e of next.head.children) {
+            if (e.tagName === 'NOSCRIPT') continue;
+            const key = e.dataset.winiKey;
+            if (key !== undefined && previous.has(key) && !e.hasAttribute('data-wini-rerun')) {
+                previous.delete(key);
+                continue;
+            }
+
+            const fresh = revive(e);
+            if (e.tagName === 'SCRIPT') {
+                scripts.push(fresh);
+                continue;
+            }
+            if (fresh.rel === 'stylesheet') loading.push(new Promise(resolve => fresh.onload = fresh.onerror = resolve));
+            document.head.append(fresh);
+        }
+        await Promise.all(loading);
+
+        if (push) history.pushState(null, '', url);
+        else if (url !== location.href) history.replaceState(null, '', url);
+        current = withoutHash(location.href);
+
+        for (const e of stale) e.remove();
+        for (const [key, e] of previous) if (e.tagName !== 'SCRIPT' || scripts.some(s => s.dataset.winiKey === key)) e.remove();
+        root.lang = next.documentElement.lang;
+        document.body.replaceWith(document.importNode(next.body, true));
+        for (const e of document.body.querySelectorAll('script')) e.replaceWith(revive(e));
+        document.head.append(...scripts);
+
+        if (push) {
+            const target = location.hash && document.getElementById(decodeURIComponent(location.hash.slice(1)));
+            target ? target.scrollIntoView() : scrollTo(0, 0);
+        }
+        document.dispatchEvent(new CustomEvent('wini:navigation', { detail: { url } }));
+    };
+
+    document.addEventListener('click', e => {
+        if (e.defaultPrevented || e.button !== 0 || e.metaKey || e.ctrlKey || e.shiftKey || e.altKey) return;
+        const a = e.target.closest?.('a');
+        if (!isPageLink(a)) return;
+        e.preventDefault();
+        navigate(a.href, true);
+    });
+
+    addEventListener('popstate', () => {
+        if (withoutHash(location.href) !== current) navigate(location.href, false);
+    });
+
+    if (root.hasAttribute('data-wini-prefetch')) {
+        const prefetch = e => {
+            const a = e.target.closest?.('a');
+            if (isPageLink(a) && withoutHash(a.href) !== current) fetchPage(withoutHash(a.href));
+        };
+        document.addEventListener('mouseover', prefetch);
+        document.addEventListener('touchstart', prefetch, { passive: true });
+        document.addEventListener('focusin', prefetch);
+    }
+})();
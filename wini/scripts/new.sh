@@ -67,9 +67,15 @@ info "Created '\e[1m$path\e[0m'."
 while [ "$relative_path" != "$src_directory_of_kind_new" ]; do
     basename="$(basename "$relative_path")"
     relative_path="$(dirname "$relative_path")"
-    if [ -e "$relative_path/mod.rs" ]; then
-        echo "pub mod $basename;" >> "$relative_path/mod.rs"
-    else
-        echo "pub mod $basename;" > "$relative_path/mod.rs"
-    fi
+    case "$basename" in
+        # `[slug]` or `[...path]` isn't a valid module name
+        \[*\])
+            module="$(echo "$basename" | sed -e 's/^\[\(\.\.\.\)\{0,1\}//' -e 's/\]$//' -e 's/-/_/g')"
+            declaration="#[path = \"$basename/mod.rs\"] pub mod $module;"
+            ;;
+        *)
+            declaration="pub mod $(echo "$basename" | sed 's/-/_/g');"
+            ;;
+    esac
+    echo "$declaration" >> "$relative_path/mod.rs"
 done
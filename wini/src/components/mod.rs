@@ -1,8 +1,15 @@
 // IFFEAT test
+pub mod scoped_card;
+
 use {
-    crate::shared::wini::err::{ServerErrorKind, ServerResult},
+    crate::shared::wini::{
+        context::ctx,
+        err::{ServerErrorKind, ServerResult},
+        json_ld::{BreadcrumbList, json_ld},
+        resource_hints::preconnect,
+    },
     cached::proc_macro::cached,
-    hyper::StatusCode,
+    hyper::{StatusCode, Uri},
     maud::{Markup, html},
     wini_macros::component,
 };
@@ -33,4 +40,80 @@ pub async fn err_component2() -> ServerResult<Markup> {
 pub async fn js_pkgs() -> ServerResult<Markup> {
     Ok(html! {})
 }
+
+#[component]
+pub async fn breadcrumb() -> ServerResult<Markup> {
+    let breadcrumb = BreadcrumbList::from_links([("Home", "/"), ("</script>", "/hello")]);
+
+    Ok(html! {
+        [json_ld(&breadcrumb)?]
+        nav {
+            a href="/" { "Home" }
+        }
+    })
+}
+
+#[component(preload = ["/fonts/inter.woff2"])]
+pub async fn hero() -> Markup {
+    html! {
+        [preconnect("https://images.example.com")]
+        img src="https://images.example.com/hero.webp";
+    }
+}
+
+/// A script linked from a CDN, which is served as is
+pub async fn cdn_script() -> Markup {
+    let mut markup = Markup::default();
+    markup
+        .linked_files
+        .insert("https://unpkg.com/htmx.org@2.0.4/dist/htmx.min.js".to_owned());
+
+    markup
+}
+
+#[derive(Debug, Clone)]
+pub struct Theme(pub &'static str);
+
+#[component]
+pub async fn themed_nav() -> Markup {
+    tokio::task::yield_now().await;
+
+    html! {
+        nav {
+            [current_path]
+        }
+    }
+}
+
+#[component]
+pub async fn current_path() -> Markup {
+    let path = ctx::<Uri>().map(|uri| uri.path().to_owned());
+    let theme = ctx::<Theme>().map_or("light", |theme| theme.0);
+
+    html! {
+        a.(theme) href=[path] { "Current page" }
+    }
+}
+
+#[component(props)]
+pub async fn card(
+    title: &str,
+    #[default] footer: Option<Markup>,
+    #[default = 2] level: u8,
+    #[default] children: Markup,
+) -> Markup {
+    html! {
+        article {
+            @if level == 1 {
+                h1 { (title) }
+            } @else {
+                h2 { (title) }
+            }
+            (children)
+            @if let Some(footer) = footer {
+                footer { (footer) }
+            }
+        }
+    }
+}
 // ENDIF
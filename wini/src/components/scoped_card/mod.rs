@@ -0,0 +1,19 @@
+// IFFEAT test
+use {
+    crate::components::button,
+    maud::{Markup, html},
+    wini_macros::component,
+};
+
+/// Styled by `./scoped.scss`, but not the button it renders
+#[component]
+pub async fn scoped_card(children: Markup) -> Markup {
+    html! {
+        article.card {
+            h2.title { "Scoped" }
+            (children)
+            [button]
+        }
+    }
+}
+// ENDIF
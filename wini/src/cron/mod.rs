@@ -1,3 +1,8 @@
+use {
+    crate::shared::wini::{config::SERVER_CONFIG, session},
+    std::time::Duration,
+};
+
 /// A macro to call a function every...
 #[macro_export]
 macro_rules! spawn_cron {
@@ -11,7 +16,18 @@ macro_rules! spawn_cron {
     };
 }
 
+/// Delete the expired sessions of the store, such as the files of the `file` store
+async fn purge_expired_sessions() {
+    if let Err(err) = session::store().purge_expired().await {
+        log::error!("Couldn't purge the expired sessions: {err}");
+    }
+}
+
 pub async fn launch_crons() {
+    if *SERVER_CONFIG.session().enabled() {
+        spawn_cron!(purge_expired_sessions, Duration::from_secs(60 * 60));
+    }
+
     // Example usage:
     // spawn_cron!(example_cron, 5.minutes());
 }
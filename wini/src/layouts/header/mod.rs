@@ -16,8 +16,11 @@ pub async fn render(s: Markup) -> ServerResult<Markup> {
 
 // IFFEAT test
 use {
-    crate::shared::wini::err::Backtrace,
-    axum::{body::Body, http::response::Parts},
+    crate::shared::{
+        app_state::AppState,
+        wini::{err::Backtrace, session::Flashes},
+    },
+    axum::{body::Body, extract::State, http::response::Parts},
     hyper::{HeaderMap, StatusCode, Uri},
 };
 
@@ -89,6 +92,36 @@ pub async fn uri(uri: Uri) -> ServerResult<Markup> {
     })
 }
 
+#[layout]
+pub async fn state(State(state): State<AppState>, s: Markup) -> Markup {
+    html! {
+        header {
+            "Welcome to " (state.name) "!"
+        }
+        (s)
+    }
+}
+
+#[layout]
+pub async fn flashes(flashes: Flashes, s: Markup) -> Markup {
+    html! {
+        @for message in &flashes {
+            p.flash { (message) }
+        }
+        (s)
+    }
+}
+
+#[layout]
+pub async fn logout(s: Markup) -> Markup {
+    html! {
+        form method="post" action="/logout" {
+            button { "Log out" }
+        }
+        (s)
+    }
+}
+
 #[layout]
 pub async fn remove_doubt(
     #[from_request_parts] _headers_req: HeaderMap,
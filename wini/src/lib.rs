@@ -6,6 +6,7 @@ pub mod components;
 pub mod cron;
 pub mod layouts;
 pub mod pages;
+pub mod router;
 pub mod server;
 pub mod shared;
 pub mod template;
@@ -10,6 +10,8 @@ use {
             config::SERVER_CONFIG,
             dependencies::SCRIPTS_DEPENDENCIES,
             packages_files::PACKAGES_FILES,
+            security_headers::SECURITY_HEADERS,
+            startup,
             tsconfig::TSCONFIG_PATHS,
         },
     },
@@ -22,6 +24,10 @@ async fn main() {
     // Init color syntaxing
     colog::init();
 
+    // Report all the problems of the environment and of the configuration at once, instead of
+    // failing on the first one
+    startup::validate().exit_if_problems();
+
     // Lock all the environment data that we will use in our application so it's not 'uninit'
     LazyLock::force(&ENV_TYPE);
     LazyLock::force(&CSS_FILES);
@@ -31,10 +37,7 @@ async fn main() {
     LazyLock::force(&PUBLIC_ENDPOINTS);
     LazyLock::force(&SCRIPTS_DEPENDENCIES);
     LazyLock::force(&SERVER_CONFIG);
-
-    // Verify that all the kind of data returned by the server (html, css, js, etc.) have their
-    // cache rules being correctly setup
-    SERVER_CONFIG.cache().verify_all_attributes();
+    LazyLock::force(&SECURITY_HEADERS);
 
     cron::launch_crons().await;
     server::start().await;
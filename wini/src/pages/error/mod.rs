@@ -0,0 +1,232 @@
+use {
+    crate::{router::route, shared::wini::err::Backtrace},
+    hyper::StatusCode,
+    maud::{Markup, html},
+    wini_macros::error_page,
+};
+
+#[error_page(404)]
+pub async fn not_found(_status_code: StatusCode, _backtrace: Option<Backtrace>) -> Markup {
+    html! {
+        h1 { "404" }
+        p { "This page doesn't exist." }
+        a href=(route!("/")) { "Go back home" }
+    }
+}
+
+#[error_page]
+pub async fn error(status_code: StatusCode, backtrace: Option<Backtrace>) -> Markup {
+    html! {
+        h1 { (status_code.as_u16()) }
+        p { (status_code.canonical_reason().unwrap_or("An error occurred.")) }
+        @if let Some(error_id) = backtrace.and_then(|backtrace| backtrace.error_id) {
+            p { "Error ID: " code { (error_id) } }
+        }
+    }
+}
+
+// IFFEAT test
+#[tokio::test]
+async fn test_error_pages() {
+    use {
+        crate::{
+            layouts::header,
+            pages::index::err_page,
+            shared::app_state::AppState,
+            template,
+            utils::wini::error_page::{error_page_middleware, not_found},
+        },
+        axum::{
+            Router,
+            middleware::{from_fn, from_fn_with_state},
+            routing::get,
+        },
+        axum_test::TestServer,
+        hyper::header::CONTENT_TYPE,
+    };
+
+    let app = Router::new()
+        .route("/err", get(err_page))
+        .route("/teapot", get(async || StatusCode::IM_A_TEAPOT))
+        .route(
+            "/json",
+            get(async || {
+                (
+                    StatusCode::BAD_REQUEST,
+                    [(CONTENT_TYPE, "application/json")],
+                    "{}",
+                )
+            }),
+        )
+        .fallback(not_found)
+        .layer(from_fn(error_page_middleware))
+        .layer(from_fn_with_state(AppState::new().await, header::render))
+        .layer(from_fn(template::template));
+    let server = TestServer::new(app).expect("creates a server");
+
+    // Unmatched route, rendered through the layout and the template
+    let resp = server.get("/does-not-exist").await;
+    resp.assert_status_not_found();
+    let txt = resp.text();
+    assert!(txt.starts_with("<!DOCTYPE html>"));
+    assert!(txt.contains("<header>Welcome to Wini!</header><h1>404</h1>"));
+
+    // Error returned by a page
+    let resp = server.get("/err").await;
+    resp.assert_status_not_found();
+    assert!(resp.text().contains("<h1>404</h1>"));
+
+    // Error without its own error page
+    let resp = server.get("/teapot").await;
+    resp.assert_status(StatusCode::IM_A_TEAPOT);
+    assert!(resp.text().contains("<h1>418</h1><p>I'm a teapot</p>"));
+
+    // Bodies meant for the client are kept
+    let resp = server.get("/json").await;
+    resp.assert_status_bad_request();
+    assert_eq!(resp.header(CONTENT_TYPE), "application/json");
+}
+
+#[tokio::test]
+async fn test_files() {
+    use {
+        crate::{
+            layouts::header,
+            shared::app_state::AppState,
+            template,
+            utils::wini::{
+                error_page::{error_page_middleware, not_found},
+                handling_file::handle_file,
+            },
+        },
+        axum::{
+            Router,
+            middleware::{from_fn, from_fn_with_state},
+            routing::get,
+        },
+        axum_test::TestServer,
+    };
+
+    let state = AppState::new().await;
+    let render_pages = |router: Router<AppState>| {
+        router
+            .layer(from_fn(error_page_middleware))
+            .layer(from_fn_with_state(state.clone(), header::render))
+            .layer(from_fn(template::template))
+            .with_state(state.clone())
+    };
+    let not_found_page = render_pages(Router::new().fallback(not_found));
+    let app = render_pages(Router::new().route("/robots.txt", get(async || "Not the file")))
+        .fallback(move |req| handle_file(req, not_found_page.clone()));
+    let server = TestServer::new(app).expect("creates a server");
+
+    // Served as is
+    let resp = server.get("/helpers.js").await;
+    resp.assert_status_ok();
+    assert_eq!(
+        resp.text(),
+        std::fs::read_to_string("./public/helpers.js").unwrap()
+    );
+
+    // The routes take precedence
+    let resp = server.get("/robots.txt").await;
+    resp.assert_status_ok();
+    assert!(resp.text().contains("Not the file"));
+
+    // Neither a route nor a file
+    for resp in [
+        server.get("/does-not-exist.js").await,
+        server.post("/helpers.js").await,
+    ] {
+        resp.assert_status_not_found();
+        assert!(
+            resp.text()
+                .contains("<header>Welcome to Wini!</header><h1>404</h1>")
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_problem_details() {
+    use {
+        crate::{
+            layouts::header,
+            pages::index::err_page,
+            shared::{app_state::AppState, wini::err::ERROR_ID_HEADER},
+            template,
+            utils::wini::{
+                error_page::{error_page_middleware, not_found},
+                problem_details::problem_details_middleware,
+            },
+        },
+        axum::{
+            Router,
+            middleware::{from_fn, from_fn_with_state},
+            routing::get,
+        },
+        axum_test::TestServer,
+        hyper::header::{ACCEPT, CONTENT_TYPE},
+        serde_json::Value,
+    };
+
+    let app = Router::new()
+        .route("/err", get(err_page))
+        .route(
+            "/internal",
+            get(async || -> crate::shared::wini::err::ServerResult<Markup> {
+                Err(std::io::Error::other("disk full"))?
+            }),
+        )
+        .fallback(not_found)
+        .layer(from_fn(error_page_middleware))
+        .layer(from_fn_with_state(AppState::new().await, header::render))
+        .layer(from_fn(template::template))
+        .layer(from_fn(problem_details_middleware));
+    let server = TestServer::new(app).expect("creates a server");
+
+    // Browsers keep getting the error page
+    let resp = server
+        .get("/does-not-exist")
+        .add_header(ACCEPT, "text/html,application/xhtml+xml,*/*;q=0.8")
+        .await;
+    resp.assert_status_not_found();
+    assert!(resp.text().contains("<h1>404</h1>"));
+
+    let resp = server
+        .get("/does-not-exist")
+        .add_header(ACCEPT, "application/json")
+        .await;
+    resp.assert_status_not_found();
+    assert_eq!(resp.header(CONTENT_TYPE), "application/problem+json");
+    assert_eq!(
+        resp.json::<Value>(),
+        serde_json::json!({
+            "type": "about:blank",
+            "title": "Not Found",
+            "status": 404,
+        })
+    );
+
+    // Error returned by a page
+    let resp = server
+        .get("/err")
+        .add_header(ACCEPT, "application/json")
+        .await;
+    resp.assert_status_not_found();
+    assert_eq!(resp.json::<Value>().get("status").unwrap(), 404);
+
+    // Unexpected error, with the ID under which it was logged
+    let resp = server
+        .get("/internal")
+        .add_header(ACCEPT, "application/json")
+        .await;
+    resp.assert_status_internal_server_error();
+    let problem = resp.json::<Value>();
+    assert_eq!(problem.get("title").unwrap(), "Internal Server Error");
+    assert_eq!(
+        problem.get("error_id").unwrap(),
+        resp.header(ERROR_ID_HEADER).to_str().unwrap()
+    );
+    assert!(problem.get("detail").unwrap().is_string());
+}
+// ENDIF
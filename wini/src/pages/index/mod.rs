@@ -0,0 +1,1119 @@
+use {
+    cached::proc_macro::cached,
+    maud::{Markup, html},
+    wini_macros::{action, init_cache, page},
+};
+
+#[init_cache]
+#[page]
+#[cached]
+pub async fn render() -> Markup {
+    html! {
+        button #hello {
+            "Say hello!"
+        }
+    }
+}
+
+// IFFEAT test
+use {
+    crate::{
+        components::{
+            CardProps,
+            Theme,
+            breadcrumb,
+            button,
+            card,
+            cdn_script,
+            err_component2,
+            hero,
+            themed_nav,
+        },
+        shared::{
+            app_state::AppState,
+            wini::{
+                action::{Action, FormErrors},
+                context::provide,
+                csp::CspNonce,
+                csrf::{CsrfToken, csrf_input},
+                err::ServerResult,
+                fragment::fragment,
+                json_ld::{Article, json_ld},
+                resource_hints::preload,
+                session::Session,
+            },
+        },
+    },
+    axum::extract::State,
+};
+
+#[page]
+pub async fn test_button() -> ServerResult<Markup> {
+    Ok(html! {
+        [button]
+        button #hello {
+            "Say hello!"
+        }
+    })
+}
+
+#[page(title = "hello world", keywords = ["hello", "world"], other_meta = ["hello" = "world", "world" = "hello"])]
+pub async fn test_meta() -> Markup {
+    html! {
+        button #hello {
+            "Say hello!"
+        }
+    }
+}
+
+#[page]
+pub async fn err_page() -> ServerResult<Markup> {
+    Ok(html! {
+        [err_component2?]
+    })
+}
+
+#[page]
+pub async fn processing_of_error() -> Markup {
+    html! {
+        [process_error(err_component2().await)]
+    }
+}
+async fn process_error(component_result: ServerResult<Markup>) -> Markup {
+    component_result.unwrap_or_else(|_| html!("An error occurred!"))
+}
+
+#[page]
+pub async fn test_json_ld() -> ServerResult<Markup> {
+    let article = Article {
+        headline: "Hello world".to_owned(),
+        ..Default::default()
+    };
+
+    Ok(html! {
+        [json_ld(&article)?]
+        [breadcrumb?]
+    })
+}
+
+#[page]
+pub async fn test_csp_nonce(nonce: CspNonce) -> Markup {
+    html! {
+        script nonce=(nonce) { "console.log('Hello world!')" }
+    }
+}
+
+#[page(preload = ["/fonts/inter.woff2"])]
+pub async fn test_resource_hints() -> Markup {
+    html! {
+        [preload("/hero.webp")]
+        [hero]
+        [cdn_script]
+    }
+}
+
+#[page]
+pub async fn test_fragment() -> Markup {
+    html! {
+        h1 { "Post" }
+        [fragment("comments", html! {
+            ul #comments { li { "First!" } }
+        })]
+        [button]
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct TestForm {
+    name: String,
+}
+
+#[page]
+pub async fn test_form(errors: FormErrors) -> Markup {
+    html! {
+        form method="post" {
+            @for error in errors.form() {
+                p.form-error { (error) }
+            }
+            input name="name" value=[errors.value("name")];
+            @for error in errors.field("name") {
+                p.error { (error) }
+            }
+        }
+    }
+}
+
+#[action(page = test_form)]
+pub async fn test_form_action(form: TestForm) -> Action {
+    if form.name.trim().is_empty() {
+        let mut errors = FormErrors::new();
+        errors.add("name", "The name is required");
+        return errors.into();
+    }
+
+    Action::redirect(format!("/hello/{}", form.name))
+}
+
+#[page]
+pub async fn test_flash() -> Markup {
+    html! {
+        form method="post" {
+            input name="name";
+        }
+    }
+}
+
+#[action(page = test_flash)]
+pub async fn test_flash_action(session: Session, form: TestForm) -> ServerResult<Action> {
+    session.flash(format!("Saved {}!", form.name))?;
+
+    Ok(Action::redirect("/flash"))
+}
+
+#[page(path = "/users/{id}")]
+pub async fn test_user(id: u32, #[query] tab: Option<String>) -> Markup {
+    let next = TestUserRoute {
+        id: id + 1,
+        tab: tab.clone(),
+    };
+
+    html! {
+        h1 { "User " (id) }
+        @if let Some(tab) = &tab {
+            p { "Tab: " (tab) }
+        }
+        a href=(next) { "Next user" }
+    }
+}
+
+#[page]
+pub async fn test_csrf_form(csrf: CsrfToken) -> Markup {
+    html! {
+        form method="post" {
+            [csrf_input(&csrf)]
+            input name="name";
+        }
+    }
+}
+
+#[page]
+pub async fn test_context() -> Markup {
+    provide(Theme("dark"));
+
+    html! {
+        [themed_nav]
+    }
+}
+
+#[page]
+pub async fn test_props() -> Markup {
+    let title = String::from("Hello");
+
+    html! {
+        [card(title = &title)]
+        [card(level = 1, title = "Bye", footer = Some(html! { "See you" }))]
+        [card(CardProps {
+            title: "Positional",
+            footer: None,
+            level: 3,
+            children: Markup::default(),
+        })]
+    }
+}
+
+#[page]
+pub async fn test_children() -> Markup {
+    html! {
+        [card(title = "Modal") {
+            p { "Body" }
+            @slot footer {
+                button { "Close" }
+            }
+        }]
+        [card(title = "Only a footer") {
+            @slot footer { "Bye" }
+        }]
+    }
+}
+
+#[page]
+pub async fn test_state(State(state): State<AppState>) -> Markup {
+    html! {
+        p { "Hello from " (state.name) "!" }
+    }
+}
+
+#[tokio::test]
+async fn test_meta_page() {
+    use {
+        crate::template,
+        axum::{Router, middleware::from_fn, routing::get},
+        axum_test::TestServer,
+    };
+
+    let app = Router::new()
+        .route("/meta", get(test_meta))
+        .layer(from_fn(template::template));
+    let server = TestServer::new(app).expect("creates a server");
+
+    let resp = server.get("/meta").await;
+
+    resp.assert_status_ok();
+    let txt = resp.text();
+    // Title
+    assert!(txt.contains("<title>hello world</title>"));
+    assert!(txt.contains(r#"<meta property="og:title" content="hello world">"#));
+    // Keywords
+    assert!(txt.contains(r#"<meta name="keywords" content="hello, world">"#));
+    // Other meta
+    assert!(txt.contains(r#"<meta name="world" content="hello">"#));
+    assert!(txt.contains(r#"<meta name="hello" content="world">"#));
+}
+
+#[tokio::test]
+async fn test_json_ld_page() {
+    use {
+        crate::template,
+        axum::{Router, middleware::from_fn, routing::get},
+        axum_test::TestServer,
+    };
+
+    let app = Router::new()
+        .route("/json-ld", get(test_json_ld))
+        .layer(from_fn(template::template));
+    let server = TestServer::new(app).expect("creates a server");
+
+    let resp = server.get("/json-ld").await;
+
+    resp.assert_status_ok();
+    let txt = resp.text();
+    let head = txt.split("</head>").next().unwrap();
+    // Both blocks are merged in the same script, in the head
+    assert_eq!(
+        head.matches(r#"<script type="application/ld+json">"#)
+            .count(),
+        1
+    );
+    assert!(head.contains(r#""@graph":["#));
+    assert!(head.contains(r#"{"@type":"Article","headline":"Hello world"}"#));
+    assert!(head.contains(r#""@type":"BreadcrumbList""#));
+    // The script tag cannot be closed from the data
+    assert!(head.contains(r#""name":"\u003c/script\u003e""#));
+    assert_eq!(head.matches("</script>").count(), 2);
+}
+
+#[tokio::test]
+async fn test_csp_nonce_page() {
+    use {
+        crate::{
+            template,
+            utils::wini::{cache, csp::csp_middleware},
+        },
+        axum::{Router, middleware::from_fn, routing::get},
+        axum_test::TestServer,
+        hyper::header::{
+            CACHE_CONTROL,
+            CONTENT_SECURITY_POLICY,
+            CONTENT_SECURITY_POLICY_REPORT_ONLY,
+        },
+    };
+
+    let app = Router::new()
+        .route("/csp", get(test_csp_nonce))
+        .layer(from_fn(template::template))
+        .layer(from_fn(cache::html_middleware))
+        .layer(from_fn(csp_middleware));
+    let server = TestServer::new(app).expect("creates a server");
+
+    let resp = server.get("/csp").await;
+
+    resp.assert_status_ok();
+    let headers = resp.headers();
+    let policy = headers
+        .get(CONTENT_SECURITY_POLICY)
+        .or_else(|| headers.get(CONTENT_SECURITY_POLICY_REPORT_ONLY))
+        .expect("a CSP header")
+        .to_str()
+        .unwrap()
+        .to_owned();
+    let nonce = policy
+        .split("'nonce-")
+        .nth(1)
+        .and_then(|rest| rest.split('\'').next())
+        .expect("a nonce in the policy");
+
+    let txt = resp.text();
+    // The same nonce is used for the template and the page
+    let nonce_attribute = format!(r#"nonce="{nonce}""#);
+    assert!(txt.contains(&format!(
+        r#"<script src="/helpers.min.js" {nonce_attribute}"#
+    )));
+    assert!(txt.contains(&format!(
+        r#"<link rel="stylesheet" href="/main.css" {nonce_attribute}>"#
+    )));
+    assert!(txt.contains(&format!("<script {nonce_attribute}>")));
+    // So it can't be cached for the other requests
+    assert_eq!(resp.header(CACHE_CONTROL), "private, no-store");
+
+    // A new nonce is generated for each request
+    let other_resp = server.get("/csp").await;
+    assert!(!other_resp.text().contains(&nonce_attribute));
+}
+
+#[tokio::test]
+async fn test_resource_hints_page() {
+    use {
+        crate::template,
+        axum::{Router, middleware::from_fn, routing::get},
+        axum_test::TestServer,
+        hyper::header::LINK,
+    };
+
+    let app = Router::new()
+        .route("/hints", get(test_resource_hints))
+        .layer(from_fn(template::template));
+    let server = TestServer::new(app).expect("creates a server");
+
+    let resp = server.get("/hints").await;
+
+    resp.assert_status_ok();
+    let txt = resp.text();
+    let head = txt.split("</head>").next().unwrap();
+    // Deduplicated between the page and the component
+    assert_eq!(
+        head.matches(r#"<link rel="preload" href="/fonts/inter.woff2" as="font" crossorigin>"#)
+            .count(),
+        1
+    );
+    assert!(head.contains(r#"<link rel="preload" href="/hero.webp" as="image">"#));
+    assert!(head.contains(r#"<link rel="preconnect" href="https://images.example.com">"#));
+    // Not prefixed by `/`, like the files of the site
+    assert!(txt.contains(r#"src="https://unpkg.com/htmx.org@2.0.4/dist/htmx.min.js""#));
+    assert!(head.contains(r#"<link rel="preconnect" href="https://unpkg.com">"#));
+
+    let link = resp.headers().get(LINK).unwrap().to_str().unwrap();
+    assert_eq!(
+        link,
+        "<https://images.example.com>; rel=preconnect, \
+         <https://unpkg.com>; rel=preconnect, \
+         </fonts/inter.woff2>; rel=preload; as=font; crossorigin, \
+         </hero.webp>; rel=preload; as=image"
+    );
+}
+
+#[tokio::test]
+async fn test_error_overlay() {
+    use {
+        crate::{shared::wini::env::EnvType, template},
+        axum::{Router, middleware::from_fn, routing::get},
+        axum_test::TestServer,
+        hyper::StatusCode,
+    };
+
+    let server = |env_type: EnvType| {
+        let app = Router::new()
+            .route("/err", get(err_page))
+            .layer(from_fn(move |req, next| {
+                template::template_for_env(env_type, req, next)
+            }));
+        TestServer::new(app).expect("creates a server")
+    };
+
+    for env_type in [EnvType::Local, EnvType::Dev] {
+        let resp = server(env_type).get("/err").await;
+
+        resp.assert_status(StatusCode::NOT_FOUND);
+        let txt = resp.text();
+        assert!(txt.contains(r#"<div id="wini-error-overlay" role="alert">"#));
+        // The trace, from where the error happened to the page
+        let component = txt.find("err_component1 - src/components/mod.rs").unwrap();
+        let page = txt.find("err_page - src/pages/index/mod.rs").unwrap();
+        assert!(component < page);
+        // The source of the component
+        assert!(txt.contains("pub async fn err_component1() -&gt; ServerResult&lt;Markup&gt; {"));
+    }
+
+    for env_type in [EnvType::Staging, EnvType::Prod] {
+        let resp = server(env_type).get("/err").await;
+
+        resp.assert_status(StatusCode::NOT_FOUND);
+        let txt = resp.text();
+        assert!(!txt.contains("wini-error-overlay"));
+        assert!(txt.contains("<h1>Error 404</h1>"));
+    }
+}
+
+#[tokio::test]
+async fn test_meta_layer_with_page() {
+    use {
+        crate::{shared::wini::layer::MetaLayerBuilder, template},
+        axum::{Router, middleware::from_fn, routing::get},
+        axum_test::TestServer,
+        std::collections::HashMap,
+    };
+
+    let app = Router::new()
+        .route("/meta", get(test_meta))
+        .route("/default", get(render))
+        .layer(
+            MetaLayerBuilder::default()
+                .default_meta(HashMap::from_iter([
+                    ("hello", "world".into()),
+                    ("world", "hello".into()),
+                    ("keywords", "hello, world".into()),
+                ]))
+                .force_meta(HashMap::from_iter([("title", "hi world!".into())]))
+                .build()
+                .unwrap(),
+        )
+        .layer(from_fn(template::template));
+    let server = TestServer::new(app).expect("creates a server");
+
+    let resp = server.get("/meta").await;
+
+    resp.assert_status_ok();
+    let txt = resp.text();
+    // Title
+    assert!(txt.contains("<title>hi world!</title>"));
+    assert!(txt.contains(r#"<meta property="og:title" content="hi world!">"#));
+    // Keywords
+    assert!(txt.contains(r#"<meta name="keywords" content="hello, world">"#));
+    // Other meta
+    assert!(txt.contains(r#"<meta name="world" content="hello">"#));
+    assert!(txt.contains(r#"<meta name="hello" content="world">"#));
+
+    let resp = server.get("/default").await;
+
+    resp.assert_status_ok();
+    let txt = resp.text();
+    // Title
+    assert!(txt.contains("<title>hi world!</title>"));
+    assert!(txt.contains(r#"<meta property="og:title" content="hi world!">"#));
+    // Keywords
+    assert!(txt.contains(r#"<meta name="keywords" content="hello, world">"#));
+    // Other meta
+    assert!(txt.contains(r#"<meta name="world" content="hello">"#));
+    assert!(txt.contains(r#"<meta name="hello" content="world">"#));
+}
+
+#[tokio::test]
+async fn test_pages_router() {
+    use {
+        crate::{
+            router::pages_router,
+            shared::{app_state::AppState, wini::scoped_css::scope_attribute},
+            template,
+        },
+        axum::middleware::from_fn,
+        axum_test::TestServer,
+    };
+
+    let state = AppState::new().await;
+    let app = pages_router(&state)
+        .layer(from_fn(template::template))
+        .with_state(state);
+    let server = TestServer::new(app).expect("creates a server");
+
+    // `./src/pages/index` => `/`
+    let resp = server.get("/").await;
+    resp.assert_status_ok();
+    assert!(
+        resp.text()
+            .contains(r#"<button id="hello">Say hello!</button>"#)
+    );
+
+    // `./src/pages/error` doesn't have a `render`
+    server.get("/error").await.assert_status_not_found();
+
+    // The elements of the page and of its layout are scoped by `./src/pages/scoped/scoped.css`
+    let attribute = scope_attribute("src/pages/scoped/scoped.css");
+    let resp = server.get("/scoped").await;
+    assert!(resp.text().contains(&format!(
+        r#"<section {attribute} class="note"><p {attribute} class="note">Scoped page</p></section>"#
+    )));
+}
+
+#[tokio::test]
+async fn test_fragments() {
+    use {
+        crate::{
+            layouts::header,
+            shared::app_state::AppState,
+            template,
+            utils::wini::fragment::fragment_middleware,
+        },
+        axum::{
+            Router,
+            middleware::{from_fn, from_fn_with_state},
+            routing::get,
+        },
+        axum_test::TestServer,
+    };
+
+    let app = Router::new()
+        .route("/post", get(test_fragment))
+        .layer(from_fn_with_state(AppState::new().await, header::render))
+        .layer(from_fn(template::template))
+        .layer(from_fn(fragment_middleware));
+    let server = TestServer::new(app).expect("creates a server");
+
+    // The full page, where the fragment markers are only comments
+    let resp = server.get("/post").await;
+    resp.assert_status_ok();
+    let txt = resp.text();
+    assert!(txt.starts_with("<!DOCTYPE html>"));
+    assert!(txt.contains("<header>Welcome to Wini!</header>"));
+    assert!(txt.contains("<!--wini-fragment:comments--><ul id=\"comments\">"));
+
+    // The body of the page, without the layouts and the template
+    let resp = server.get("/post").add_header("HX-Request", "true").await;
+    resp.assert_status_ok();
+    let txt = resp.text();
+    assert!(!txt.contains("<!DOCTYPE html>"));
+    assert!(!txt.contains("<header>"));
+    assert!(txt.contains("<h1>Post</h1>"));
+
+    // The boosted links of htmx replace the whole page
+    let resp = server
+        .get("/post")
+        .add_header("HX-Request", "true")
+        .add_header("HX-Boosted", "true")
+        .await;
+    assert!(resp.text().starts_with("<!DOCTYPE html>"));
+
+    // A named fragment
+    for resp in [
+        server.get("/post?fragment=comments").await,
+        server
+            .get("/post")
+            .add_header("X-Wini-Fragment", "comments")
+            .await,
+    ] {
+        resp.assert_status_ok();
+        assert_eq!(
+            resp.header("vary"),
+            "hx-request, hx-boosted, x-wini-fragment"
+        );
+        let txt = resp.text();
+        assert!(!txt.contains("<h1>"));
+        assert!(txt.ends_with(r#"<ul id="comments"><li>First!</li></ul>"#));
+    }
+
+    server
+        .get("/post?fragment=unknown")
+        .await
+        .assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_form_action_page() {
+    use {
+        crate::{layouts::header, shared::app_state::AppState, template},
+        axum::{
+            Router,
+            middleware::{from_fn, from_fn_with_state},
+            routing::get,
+        },
+        axum_test::TestServer,
+        hyper::{StatusCode, header::LOCATION},
+    };
+
+    let state = AppState::new().await;
+    let app = Router::new()
+        .route("/form", get(test_form).post(test_form_action))
+        .layer(from_fn_with_state(state.clone(), header::render))
+        .layer(from_fn(template::template))
+        .with_state(state);
+    let server = TestServer::new(app).expect("creates a server");
+
+    // Redirected after the post
+    let resp = server.post("/form").form(&[("name", "wini")]).await;
+    resp.assert_status(StatusCode::SEE_OTHER);
+    assert_eq!(resp.header(LOCATION), "/hello/wini");
+
+    // The page is rendered again with the errors and the submitted values
+    let resp = server.post("/form").form(&[("name", " ")]).await;
+    resp.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    let txt = resp.text();
+    assert!(txt.contains("<header>Welcome to Wini!</header>"));
+    assert!(txt.contains(r#"<input name="name" value=" ">"#));
+    assert!(txt.contains(r#"<p class="error">The name is required</p>"#));
+
+    // A form that can't be deserialized
+    let resp = server.post("/form").form(&[("other", "")]).await;
+    resp.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(
+        resp.text()
+            .contains(r#"<p class="form-error">missing field `name`</p>"#)
+    );
+
+    // With htmx
+    let resp = server
+        .post("/form")
+        .add_header("HX-Request", "true")
+        .form(&[("name", "wini")])
+        .await;
+    resp.assert_status_ok();
+    assert_eq!(resp.header("hx-redirect"), "/hello/wini");
+
+    let resp = server
+        .post("/form")
+        .add_header("HX-Request", "true")
+        .form(&[("name", "")])
+        .await;
+    resp.assert_status_ok();
+    assert!(resp.text().contains("The name is required"));
+}
+
+#[tokio::test]
+async fn test_flash_page() {
+    use {
+        crate::{
+            layouts::header,
+            shared::{app_state::AppState, wini::config::ConfigSession},
+            template,
+            utils::wini::session::session,
+        },
+        axum::{
+            Router,
+            middleware::{from_fn, from_fn_with_state},
+            routing::get,
+        },
+        axum_test::TestServer,
+        hyper::{
+            StatusCode,
+            header::{CACHE_CONTROL, COOKIE, LOCATION, SET_COOKIE, VARY},
+        },
+    };
+
+    let config: &'static ConfigSession =
+        Box::leak(Box::new(toml::from_str("enabled = true").unwrap()));
+    let state = AppState::new().await;
+    let app = Router::new()
+        .route("/flash", get(test_flash).post(test_flash_action))
+        .layer(from_fn_with_state(state.clone(), header::flashes))
+        .layer(from_fn(move |req, next| session(config, req, next)))
+        .layer(from_fn(template::template))
+        .with_state(state);
+    let server = TestServer::new(app).expect("creates a server");
+    let flash = r#"<p class="flash">Saved wini!</p>"#;
+
+    // Without a session, the page can be cached
+    let resp = server.get("/flash").await;
+    assert!(resp.maybe_header(SET_COOKIE).is_none());
+    assert!(resp.maybe_header(CACHE_CONTROL).is_none());
+
+    let resp = server.post("/flash").form(&[("name", "wini")]).await;
+    resp.assert_status(StatusCode::SEE_OTHER);
+    assert_eq!(resp.header(LOCATION), "/flash");
+    assert_eq!(resp.header(CACHE_CONTROL), "private, no-store");
+    assert_eq!(resp.header(VARY), "Cookie");
+    // Not read by the layout of the redirection
+    assert!(!resp.text().contains(flash));
+    let cookie = resp
+        .header(SET_COOKIE)
+        .to_str()
+        .unwrap()
+        .split(';')
+        .next()
+        .unwrap()
+        .to_owned();
+
+    // Shown by the layout of the next page
+    let resp = server.get("/flash").add_header(COOKIE, &cookie).await;
+    resp.assert_status_ok();
+    assert_eq!(resp.text().matches(flash).count(), 1);
+    assert_eq!(resp.header(CACHE_CONTROL), "private, no-store");
+
+    // Only once
+    let resp = server.get("/flash").add_header(COOKIE, &cookie).await;
+    assert!(!resp.text().contains(flash));
+}
+
+#[tokio::test]
+async fn test_csrf() {
+    use {
+        crate::{
+            layouts::header,
+            shared::{app_state::AppState, wini::config::ConfigCsrf},
+            template,
+            utils::wini::csrf::csrf,
+        },
+        axum::{
+            Router,
+            middleware::{from_fn, from_fn_with_state},
+            routing::{get, post},
+        },
+        axum_test::{
+            TestServer,
+            multipart::{MultipartForm, Part},
+        },
+        hyper::{
+            StatusCode,
+            header::{AUTHORIZATION, CACHE_CONTROL, COOKIE, SET_COOKIE, VARY},
+        },
+    };
+
+    let app = |mode: &str| {
+        let config: &'static ConfigCsrf = Box::leak(Box::new(
+            toml::from_str(&format!("enabled = true\nmode = {mode:?}")).unwrap(),
+        ));
+
+        async move {
+            Router::new()
+                .route("/", get(render))
+                .route("/form", get(test_form).post(test_form_action))
+                .route("/csrf-form", get(test_csrf_form))
+                .route("/upload", post(async || StatusCode::NO_CONTENT))
+                .layer(from_fn(move |req, next| csrf(config, req, next)))
+                .layer(from_fn(template::template))
+                .with_state(AppState::new().await)
+        }
+    };
+    let cookie_of = |set_cookie: &str| set_cookie.split(';').next().unwrap().to_owned();
+    let input = |token: &str| format!(r#"<input type="hidden" name="_csrf" value="{token}">"#);
+    let meta_token = |html: &str| {
+        html.split(r#"<meta name="csrf-token" content=""#)
+            .nth(1)
+            .map(|rest| rest.split('"').next().unwrap().to_owned())
+    };
+
+    let server = TestServer::new(app("double_submit").await).expect("creates a server");
+
+    // A new session, that can't be cached by the others
+    let resp = server.get("/form").await;
+    resp.assert_status_ok();
+    assert_eq!(resp.header(CACHE_CONTROL), "private, no-store");
+    assert_eq!(resp.header(VARY), "Cookie");
+    let cookie = cookie_of(resp.header(SET_COOKIE).to_str().unwrap());
+    let session_id = cookie.strip_prefix("wini_csrf=").unwrap();
+    let token = meta_token(&resp.text()).unwrap();
+    // The token is signed, not a copy of the cookie
+    assert_ne!(token, session_id);
+    assert_eq!(token.len(), 43);
+    // And added to the form of the action
+    assert!(
+        resp.text()
+            .contains(&format!(r#"<form method="post">{}"#, input(&token)))
+    );
+
+    // The same session
+    let resp = server.get("/form").add_header(COOKIE, &cookie).await;
+    assert!(resp.maybe_header(SET_COOKIE).is_none());
+    assert_eq!(meta_token(&resp.text()), Some(token.clone()));
+
+    // Without the token
+    server
+        .post("/form")
+        .add_header(COOKIE, &cookie)
+        .form(&[("name", "wini")])
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .post("/form")
+        .form(&[("name", "wini"), ("_csrf", &token)])
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    // With the id of the session instead
+    server
+        .post("/form")
+        .add_header(COOKIE, &cookie)
+        .form(&[("name", "wini"), ("_csrf", session_id)])
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    // With the token in the form, or in the header
+    server
+        .post("/form")
+        .add_header(COOKIE, &cookie)
+        .form(&[("name", "wini"), ("_csrf", &token)])
+        .await
+        .assert_status(StatusCode::SEE_OTHER);
+    server
+        .post("/form")
+        .add_header(COOKIE, &cookie)
+        .add_header("X-CSRF-Token", &token)
+        .form(&[("name", "wini")])
+        .await
+        .assert_status(StatusCode::SEE_OTHER);
+
+    // With the token in a form uploading a file
+    let upload = |token: &str| {
+        MultipartForm::new()
+            .add_part("file", Part::bytes(&b"--\r\n\r\n"[..]).file_name("a.txt"))
+            .add_text("_csrf", token.to_owned())
+    };
+    server
+        .post("/upload")
+        .add_header(COOKIE, &cookie)
+        .multipart(upload(session_id))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .post("/upload")
+        .add_header(COOKIE, &cookie)
+        .multipart(upload(&token))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    // The page rendered again by the action keeps the token
+    let resp = server
+        .post("/form")
+        .add_header(COOKIE, &cookie)
+        .form(&[("name", ""), ("_csrf", &token)])
+        .await;
+    resp.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(resp.text().matches(&input(&token)).count(), 1);
+
+    // Authenticated with a bearer token
+    server
+        .post("/form")
+        .add_header(AUTHORIZATION, "Bearer abc")
+        .form(&[("name", "wini")])
+        .await
+        .assert_status(StatusCode::SEE_OTHER);
+
+    let server = TestServer::new(app("synchronizer").await).expect("creates a server");
+
+    // No session is created by the pages that don't read the token, or don't have a form
+    let resp = server.get("/").await;
+    assert!(resp.maybe_header(SET_COOKIE).is_none());
+    assert!(meta_token(&resp.text()).is_none());
+    assert!(resp.maybe_header(CACHE_CONTROL).is_none());
+
+    let resp = server.get("/csrf-form").await;
+    assert_eq!(resp.header(CACHE_CONTROL), "private, no-store");
+    let cookie = cookie_of(resp.header(SET_COOKIE).to_str().unwrap());
+    let html = resp.text();
+    let token = meta_token(&html).unwrap();
+    // Not added again
+    assert_eq!(html.matches(&input(&token)).count(), 1);
+
+    // The session is kept by the server
+    let resp = server.get("/form").add_header(COOKIE, &cookie).await;
+    assert!(resp.maybe_header(SET_COOKIE).is_none());
+    assert_eq!(meta_token(&resp.text()), Some(token.clone()));
+    assert!(resp.text().contains(&input(&token)));
+
+    // A form creates a session
+    let resp = server.get("/form").await;
+    assert!(resp.maybe_header(SET_COOKIE).is_some());
+    assert!(meta_token(&resp.text()).is_some());
+    server
+        .post("/form")
+        .add_header(COOKIE, &cookie)
+        .form(&[("name", "wini"), ("_csrf", &token)])
+        .await
+        .assert_status(StatusCode::SEE_OTHER);
+    server
+        .post("/form")
+        .add_header(COOKIE, "wini_csrf=unknown")
+        .form(&[("name", "wini"), ("_csrf", &token)])
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    // The forms of the layouts, that are outside of the middleware, get it too
+    let config: &'static ConfigCsrf = Box::leak(Box::new(
+        toml::from_str("enabled = true\nmode = \"synchronizer\"").unwrap(),
+    ));
+    let state = AppState::new().await;
+    let app = Router::new()
+        .route("/", get(render))
+        .layer(from_fn(move |req, next| csrf(config, req, next)))
+        .layer(from_fn_with_state(state.clone(), header::logout))
+        .layer(from_fn(template::template))
+        .with_state(state);
+    let server = TestServer::new(app).expect("creates a server");
+
+    let resp = server.get("/").await;
+    assert!(resp.maybe_header(SET_COOKIE).is_some());
+    let token = meta_token(&resp.text()).unwrap();
+    assert!(resp.text().contains(&format!(
+        r#"<form method="post" action="/logout">{}"#,
+        input(&token)
+    )));
+}
+
+#[tokio::test]
+async fn test_typed_route() {
+    use {
+        crate::router::route,
+        axum::{Router, routing::get},
+        axum_test::TestServer,
+        hyper::StatusCode,
+    };
+
+    assert_eq!(TestUserRoute::PATH, "/users/{id}");
+    assert_eq!(TestUserRoute { id: 7, tab: None }.url(), "/users/7");
+    assert_eq!(
+        TestUserRoute {
+            id: 7,
+            tab: Some("posts & likes".to_owned()),
+        }
+        .to_string(),
+        "/users/7?tab=posts+%26+likes"
+    );
+    assert_eq!(route!("/users/{id}", id = 7), "/users/7");
+
+    let app = Router::new().route(TestUserRoute::PATH, get(test_user));
+    let server = TestServer::new(app).expect("creates a server");
+
+    let resp = server
+        .get("/users/42")
+        .add_query_param("tab", "posts")
+        .await;
+    resp.assert_status_ok();
+    let txt = resp.text();
+    assert!(txt.contains("<h1>User 42</h1>"));
+    assert!(txt.contains("<p>Tab: posts</p>"));
+    assert!(txt.contains(r#"<a href="/users/43?tab=posts">Next user</a>"#));
+
+    let resp = server.get("/users/42").await;
+    assert!(!resp.text().contains("Tab:"));
+
+    // Not a `u32`
+    server
+        .get("/users/alice")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_state_page() {
+    use {
+        crate::{layouts::header, template},
+        axum::{
+            Router,
+            middleware::{from_fn, from_fn_with_state},
+            routing::get,
+        },
+        axum_test::TestServer,
+    };
+
+    // Another name than the one of `AppState::new`
+    let state = AppState { name: "the state" };
+    let app = Router::new()
+        .route("/state", get(test_state))
+        .layer(from_fn_with_state(state.clone(), header::state))
+        .layer(from_fn(template::template))
+        .with_state(state);
+    let server = TestServer::new(app).expect("creates a server");
+
+    let resp = server.get("/state").await;
+    resp.assert_status_ok();
+    let txt = resp.text();
+    // Read by the layout and by the page
+    assert!(txt.contains("<header>Welcome to the state!</header>"));
+    assert!(txt.contains("<p>Hello from the state!</p>"));
+}
+
+#[tokio::test]
+async fn test_context_page() {
+    use {
+        crate::utils::wini::context::context_middleware,
+        axum::{Router, middleware::from_fn, routing::get},
+        axum_test::TestServer,
+    };
+
+    let app = Router::new()
+        .route("/context", get(test_context))
+        .layer(from_fn(context_middleware));
+    let server = TestServer::new(app).expect("creates a server");
+
+    // The nested component reads the path of the request and the theme provided by the page
+    let resp = server.get("/context").await;
+    resp.assert_status_ok();
+    assert!(
+        resp.text()
+            .contains(r#"<nav><a class="dark" href="/context">Current page</a></nav>"#)
+    );
+}
+
+#[tokio::test]
+async fn test_props_page() {
+    use {
+        axum::{Router, routing::get},
+        axum_test::TestServer,
+    };
+
+    let app = Router::new().route("/props", get(test_props));
+    let server = TestServer::new(app).expect("creates a server");
+
+    let resp = server.get("/props").await;
+    resp.assert_status_ok();
+    assert_eq!(
+        resp.text(),
+        concat!(
+            "<article><h2>Hello</h2></article>",
+            "<article><h1>Bye</h1><footer>See you</footer></article>",
+            "<article><h2>Positional</h2></article>",
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_children_page() {
+    use {
+        axum::{Router, routing::get},
+        axum_test::TestServer,
+    };
+
+    let app = Router::new().route("/children", get(test_children));
+    let server = TestServer::new(app).expect("creates a server");
+
+    let resp = server.get("/children").await;
+    resp.assert_status_ok();
+    assert_eq!(
+        resp.text(),
+        concat!(
+            "<article><h2>Modal</h2><p>Body</p><footer><button>Close</button></footer></article>",
+            "<article><h2>Only a footer</h2><footer>Bye</footer></article>",
+        )
+    );
+}
+
+// IFFEAT ssg
+#[tokio::test]
+async fn test_ssg_context() {
+    use {
+        crate::{
+            shared::wini::{
+                context::{Context, ctx},
+                ssg::{IS_GENERATING, SsgRouter},
+            },
+            utils::wini::context::context_middleware,
+        },
+        axum::{
+            middleware::{Next, from_fn},
+            routing::get,
+        },
+        axum_test::TestServer,
+        std::sync::atomic::Ordering,
+    };
+
+    let app = SsgRouter::new()
+        .route(
+            "/theme",
+            get(async || html! { (ctx::<Theme>().map_or("none", |theme| theme.0)) }),
+        )
+        .context(|_uri| Context::new().with(Theme("light")))
+        .into_axum_router()
+        // The theme of the client
+        .layer(from_fn(async |req, next: Next| {
+            provide(Theme("dark"));
+            next.run(req).await
+        }))
+        .layer(from_fn(context_middleware))
+        .with_state(AppState::new().await);
+    let server = TestServer::new(app).expect("creates a server");
+
+    // Served to a client, with `run-with-ssr`
+    assert_eq!(server.get("/theme").await.text(), "dark");
+
+    IS_GENERATING.store(true, Ordering::Relaxed);
+    let resp = server.get("/theme").await;
+    IS_GENERATING.store(false, Ordering::Relaxed);
+    assert_eq!(resp.text(), "light");
+}
+// ENDIF
+// ENDIF
@@ -1 +1,5 @@
-pub mod hello;
+pub mod error;
+pub mod index;
+// IFFEAT test
+pub mod scoped;
+// ENDIF
@@ -0,0 +1,23 @@
+// IFFEAT test
+use {
+    maud::{Markup, html},
+    wini_macros::{layout, page},
+};
+
+/// Styled by `./scoped.scss`, with its layout
+#[page]
+pub async fn render() -> Markup {
+    html! {
+        p.note { "Scoped page" }
+    }
+}
+
+#[layout]
+pub async fn layout(s: Markup) -> Markup {
+    html! {
+        section.note {
+            (s)
+        }
+    }
+}
+// ENDIF
@@ -0,0 +1,30 @@
+//! The router of the pages, generated by `./build.rs` from the directories of `path.pages`.
+//!
+//! A directory is a page if its `mod.rs` has a `pub async fn render`, and its route is the path of
+//! the directory:
+//! - `pages/index/mod.rs` => `/`
+//! - `pages/about/mod.rs` => `/about`
+//! - `pages/blog/[slug]/mod.rs` => `/blog/{slug}`
+//! - `pages/docs/[...path]/mod.rs` => `/docs/{*path}`
+//!
+//! Directories with a parameter must be declared with a `#[path]`:
+//! `#[path = "[slug]/mod.rs"] pub mod slug;`
+//!
+//! The parameters are given to the page by `#[page(path = "...")]`, which must be the route of its
+//! directory: `#[page(path = "/blog/{slug}")] pub async fn render(slug: String)`.
+//!
+//! The links to the pages are built with [`route!`], which doesn't compile if the route doesn't
+//! exist anymore: `a href=(route!("/blog/{slug}", slug = post.slug)) { "Read more" }`.
+//!
+//! A `#[action] pub async fn action` in the `mod.rs` of a page handles the forms posted to its
+//! route.
+//!
+//! A `#[layout] pub async fn layout` in the `mod.rs` of a directory is applied to all the pages in
+//! this directory and its sub-directories.
+// IFFEAT ssg
+//!
+//! The pages with parameters are rendered statically with the parameters returned by
+//! `pub fn ssg_params() -> Vec<Vec<Cow<'static, str>>>`, in the same `mod.rs`.
+// ENDIF
+
+include!(concat!(env!("OUT_DIR"), "/routes.rs"));
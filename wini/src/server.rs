@@ -4,21 +4,33 @@
 use {
     crate::{
         layouts::header,
-        pages,
-        shared::wini::{
-            layer::MetaLayerBuilder,
+        router::pages_router,
+        shared::{
+            app_state::AppState,
+            wini::{
+                base_path,
+                layer::MetaLayerBuilder,
 // IFFEAT ssg
-            ssg::{render_routes_to_files, SsgRouter},
+                ssg::render_routes_to_files,
 // ENDIF
-            PORT,
+                PORT,
+            },
         },
         template,
         utils::wini::{
             cache,
+            context::context_middleware,
+            csp,
+            csrf::csrf_middleware,
+            error_page::{error_page_middleware, not_found},
+            fragment::fragment_middleware,
             handling_file::{self},
+            problem_details::problem_details_middleware,
+            security_headers::security_headers_middleware,
+            session::session_middleware,
         },
     },
-    axum::{middleware, routing::get, Router},
+    axum::{middleware, Router},
     log::info,
     std::collections::HashMap,
     tower_http::compression::CompressionLayer,
@@ -29,29 +41,26 @@ use {
 #[rustfmt::skip]
 // ENDIF
 pub async fn start() {
+    let state = AppState::new().await;
+
 // IFFEAT ssr
     // The main router of the application is defined here
-    let app = Router::<()>::new()
+    let pages = Router::<AppState>::new()
+        .merge(pages_router(&state));
 // IFFEAT test
-        .route("/test", get(pages::hello::err_page))
+    let pages = pages
+        .route("/test", axum::routing::get(crate::pages::index::err_page));
         // .layer(middleware::from_fn(f))
 // ENDIF
-        .route("/", get(pages::hello::render))
-        .layer(middleware::from_fn(header::render))
-        .layer(
-            MetaLayerBuilder::default()
-                .default_meta(HashMap::from_iter([
-                        ("title", "PROJECT_NAME_TO_RESOLVE".into()),
-                        ("description", "PROJECT_NAME_TO_RESOLVE".into()),
-                        ("lang", "en".into()),
-                ]))
-                .build()
-                .expect("Failed to build MetaLayer"),
-        )
-        .layer(middleware::from_fn(template::template))
-        .layer(middleware::from_fn(cache::html_middleware))
-        .route("/{*wildcard}", get(handling_file::handle_file))
-        .layer(CompressionLayer::new());
+    let not_found_page = render_pages(Router::new().fallback(not_found), &state)
+        .with_state(state.clone());
+    let app = render_pages(pages, &state)
+        // The files, and then the error page, for the routes that don't exist
+        .fallback(move |req| handling_file::handle_file(req, not_found_page.clone()))
+        .layer(middleware::from_fn(security_headers_middleware))
+        .layer(CompressionLayer::new())
+        .with_state(state.clone());
+    let app = base_path::nest(app);
 
 
     // Start the server
@@ -68,18 +77,13 @@ pub async fn start() {
 // IFFEAT ssg
     #[cfg(any(feature = "generate-ssg", feature = "run-with-ssr"))]
     {
-// IFFEAT test
-        #[allow(unused, reason = "test")]
-// ENDIF
-        let ssg_router = SsgRouter::new()
-            .route("/", get(pages::hello::render));
+        let ssg_router = crate::router::pages_ssg_router(&state);
 // IFFEAT test
         use maud::html;
-        let ssg_router = SsgRouter::new()
-            .route("/", get(pages::hello::render))
+        let ssg_router = ssg_router
             .route_with_params(
                 "/param-test/{hey}",
-                get(async |p: axum::extract::Path<String>| html! {"test: "(p.to_string())}),
+                axum::routing::get(async |p: axum::extract::Path<String>| html! {"test: "(p.to_string())}),
                 vec![
                     vec!["hello".into()],
                     vec!["world".into()]
@@ -89,23 +93,16 @@ pub async fn start() {
 
 
         // The main router of the application is defined here
-        let app = Router::<()>::new()
-            .merge(ssg_router.into_axum_router())
-            .layer(middleware::from_fn(header::render))
-            .layer(
-                MetaLayerBuilder::default()
-                    .default_meta(HashMap::from_iter([
-                        ("title", "PROJECT_NAME_TO_RESOLVE".into()),
-                        ("description", "PROJECT_NAME_TO_RESOLVE".into()),
-                        ("lang", "en".into()),
-                    ]))
-                    .build()
-                    .expect("Failed to build MetaLayer"),
-            )
-            .layer(middleware::from_fn(template::template))
-            .layer(middleware::from_fn(cache::html_middleware))
-            .route("/{*wildcard}", get(handling_file::handle_file))
-            .layer(CompressionLayer::new());
+        let pages = Router::<AppState>::new()
+            .merge(ssg_router.into_axum_router());
+        let not_found_page = render_pages(Router::new().fallback(not_found), &state)
+            .with_state(state.clone());
+        let app = render_pages(pages, &state)
+            .fallback(move |req| handling_file::handle_file(req, not_found_page.clone()))
+            .layer(middleware::from_fn(security_headers_middleware))
+            .layer(CompressionLayer::new())
+            .with_state(state);
+        let app = base_path::nest(app);
 
         // Start the server
         info!("Starting listening on port {}...", *PORT);
@@ -145,6 +142,7 @@ pub async fn start() {
     {
         let app = Router::<()>::new()
             .nest_service("/", tower_http::services::ServeDir::new("dist"))
+            .layer(middleware::from_fn(security_headers_middleware))
             .layer(CompressionLayer::new());
 
         // Start the server
@@ -160,3 +158,29 @@ pub async fn start() {
     }
 // ENDIF
 }
+
+
+/// The layers rendering the pages and the error pages, from the innermost to the outermost
+fn render_pages(router: Router<AppState>, state: &AppState) -> Router<AppState> {
+    router
+        .layer(middleware::from_fn(csrf_middleware))
+        .layer(middleware::from_fn(error_page_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), header::render))
+        .layer(
+            MetaLayerBuilder::default()
+                .default_meta(HashMap::from_iter([
+                    ("title", "PROJECT_NAME_TO_RESOLVE".into()),
+                    ("description", "PROJECT_NAME_TO_RESOLVE".into()),
+                    ("lang", "en".into()),
+                ]))
+                .build()
+                .expect("Failed to build MetaLayer"),
+        )
+        .layer(middleware::from_fn(session_middleware))
+        .layer(middleware::from_fn(template::template))
+        .layer(middleware::from_fn(context_middleware))
+        .layer(middleware::from_fn(fragment_middleware))
+        .layer(middleware::from_fn(problem_details_middleware))
+        .layer(middleware::from_fn(cache::html_middleware))
+        .layer(middleware::from_fn(csp::csp_middleware))
+}
@@ -0,0 +1,53 @@
+//! The state shared by all the requests, such as a pool of connections to a database.
+//!
+//! It's created once in `server::start`, and given to the router, so that the pages, the actions
+//! and the layouts can extract it with [`axum::extract::State<AppState>`]. The components aren't
+//! handlers: the state, or the part of it they need, is given to them as an argument.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! #[derive(Debug, Clone)]
+//! pub struct AppState {
+//!     pub db: sqlx::PgPool,
+//! }
+//!
+//! #[page]
+//! pub async fn render(State(state): State<AppState>) -> ServerResult<Markup> {
+//!     let posts = Post::all(&state.db).await?;
+//!
+//!     Ok(html! {
+//!         @for post in posts {
+//!             [post_card(&post)]
+//!         }
+//!     })
+//! }
+//! ```
+
+
+/// The state of the application. Its fields must be cheap to clone: wrap them in an
+/// [`std::sync::Arc`] if they aren't.
+// IFFEAT remove
+#[rustfmt::skip]
+// ENDIF
+#[derive(Debug, Clone)]
+pub struct AppState {
+// IFFEAT test
+    /// The name of the site, read by the test pages and layouts
+    pub name: &'static str,
+// ENDIF
+}
+
+// IFFEAT remove
+#[rustfmt::skip]
+// ENDIF
+impl AppState {
+    /// Create the state, before the server starts
+    pub async fn new() -> Self {
+        Self {
+// IFFEAT test
+            name: "Wini",
+// ENDIF
+        }
+    }
+}
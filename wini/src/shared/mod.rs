@@ -1,2 +1,4 @@
+/// The state of the application
+pub mod app_state;
 /// Core shared for wini
 pub mod wini;
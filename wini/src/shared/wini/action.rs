@@ -0,0 +1,193 @@
+//! The forms posted to a page, handled by its `#[action]`.
+//!
+//! An action receives the form deserialized from the body of the request, and returns either:
+//! - [`Action::Redirect`], sent as a `303 See Other` so that reloading the next page doesn't post
+//!   the form again,
+//! - [`Action::Invalid`], to render the page again with the [`FormErrors`], with a
+//!   `422 Unprocessable Entity`.
+//!
+//! It works with plain HTML forms. With htmx, the redirections are sent with a `HX-Redirect`
+//! header, and the page is rendered with a `200 OK`, since htmx doesn't swap the error responses.
+//!
+//! With `[security.csrf]`, the token of the session is added to the forms of the page by the
+//! template, when it's rendered and when it's rendered again.
+
+use {
+    super::err::ServerError,
+    crate::shared::app_state::AppState,
+    axum::{
+        body::Body,
+        extract::{FromRequestParts, Request},
+        handler::Handler,
+        http::{HeaderValue, Method, request::Parts},
+        response::{IntoResponse, Response},
+    },
+    hyper::{
+        StatusCode,
+        header::{CONTENT_LENGTH, CONTENT_TYPE, LOCATION},
+    },
+    serde::de::DeserializeOwned,
+    std::{
+        collections::{BTreeMap, HashMap},
+        convert::Infallible,
+        fmt::Display,
+    },
+};
+
+
+/// The result of an `#[action]`
+#[derive(Debug)]
+pub enum Action {
+    /// Redirect to another page, usually a typed route or a `route!`
+    Redirect(String),
+    /// Render the page of the action again, with the errors of the form
+    Invalid(FormErrors),
+}
+
+impl Action {
+    pub fn redirect(to: impl Display) -> Self {
+        Self::Redirect(to.to_string())
+    }
+}
+
+impl From<FormErrors> for Action {
+    fn from(errors: FormErrors) -> Self {
+        Self::Invalid(errors)
+    }
+}
+
+
+/// The errors of a form posted to an `#[action]`, and the values that were submitted.
+///
+/// The page of the action can use it as an extractor: it's empty unless the page is rendered
+/// again by [`Action::Invalid`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[page]
+/// pub async fn render(errors: FormErrors) -> Markup {
+///     html! {
+///         form method="post" {
+///             input name="email" value=[errors.value("email")];
+///             @for error in errors.field("email") {
+///                 p.error { (error) }
+///             }
+///             button { "Subscribe" }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FormErrors {
+    fields: BTreeMap<String, Vec<String>>,
+    form: Vec<String>,
+    values: HashMap<String, String>,
+}
+
+impl FormErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an error to a field of the form
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.fields
+            .entry(field.to_owned())
+            .or_default()
+            .push(message.into());
+    }
+
+    /// Add an error that isn't about a specific field
+    pub fn add_to_form(&mut self, message: impl Into<String>) {
+        self.form.push(message.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.form.is_empty()
+    }
+
+    /// The errors of a field
+    pub fn field(&self, field: &str) -> &[String] {
+        self.fields.get(field).map_or(&[], Vec::as_slice)
+    }
+
+    /// The errors that aren't about a specific field, such as a form that couldn't be deserialized
+    pub fn form(&self) -> &[String] {
+        &self.form
+    }
+
+    /// The value submitted for a field, to fill the form again
+    pub fn value(&self, field: &str) -> Option<&str> {
+        self.values.get(field).map(String::as_str)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for FormErrors {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts.extensions.get::<Self>().cloned().unwrap_or_default())
+    }
+}
+
+
+/// Deserialize the body of a form. Used by `#[action]`.
+#[doc(hidden)]
+pub fn parse_form<T: DeserializeOwned>(body: &[u8]) -> Result<T, FormErrors> {
+    serde_urlencoded::from_bytes(body).map_err(|err| {
+        let mut errors = FormErrors::new();
+        errors.add_to_form(err.to_string());
+        errors
+    })
+}
+
+/// The response of an action. Used by `#[action]`.
+///
+/// The page is rendered by calling its handler with the request of the action, turned into a
+/// `GET`, so that it has the same extractors as when it's requested directly.
+#[doc(hidden)]
+pub async fn respond<H, T>(
+    action: Action,
+    mut parts: Parts,
+    body: &[u8],
+    page: H,
+    state: AppState,
+) -> Response
+where
+    H: Handler<T, AppState>,
+{
+    let is_htmx = parts.headers.contains_key("hx-request");
+
+    match action {
+        Action::Redirect(location) => {
+            let location = match HeaderValue::from_str(&location) {
+                Ok(location) => location,
+                Err(err) => return ServerError::from(err).into_response(),
+            };
+
+            if is_htmx {
+                [("hx-redirect", location)].into_response()
+            } else {
+                (StatusCode::SEE_OTHER, [(LOCATION, location)]).into_response()
+            }
+        },
+        Action::Invalid(mut errors) => {
+            errors.values = serde_urlencoded::from_bytes(body).unwrap_or_default();
+
+            parts.method = Method::GET;
+            parts.headers.remove(CONTENT_TYPE);
+            parts.headers.remove(CONTENT_LENGTH);
+            parts.extensions.insert(errors);
+
+            let mut resp = page
+                .call(Request::from_parts(parts, Body::empty()), state)
+                .await;
+            if !is_htmx && resp.status().is_success() {
+                *resp.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
+            }
+
+            resp
+        },
+    }
+}
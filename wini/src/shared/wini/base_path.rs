@@ -0,0 +1,79 @@
+//! The deployment of the site under a sub-path, such as `https://example.com/docs/`, with
+//! `base_path` in `./wini.toml`.
+//!
+//! The routes, the pages and the files are all defined from `/`: the base path is removed from the
+//! requests by [`nest`], and added to the URLs sent to the clients by [`with_base_path`].
+
+use {
+    super::config::SERVER_CONFIG,
+    axum::{Router, response::Redirect, routing::get},
+    std::borrow::Cow,
+};
+
+
+/// The base path without trailing `/`, and empty if the site is served at the root.
+///
+/// `docs/` => `/docs`, `/` => ``
+pub fn normalize(base_path: &str) -> String {
+    let base_path = base_path.trim().trim_matches('/');
+
+    if base_path.is_empty() {
+        String::new()
+    } else {
+        format!("/{base_path}")
+    }
+}
+
+/// The characters that can't be in the base path, because they would be part of a route or of the
+/// query of the URLs
+pub fn invalid_chars(base_path: &str) -> Vec<char> {
+    base_path
+        .chars()
+        .filter(|c| matches!(c, '{' | '}' | '*' | '?' | '#') || c.is_whitespace())
+        .collect()
+}
+
+/// Whether a URL points to another site, such as `https://cdn.example.com/lib.js` or
+/// `//cdn.example.com/lib.js`
+pub fn is_external(url: &str) -> bool {
+    url.starts_with("//") ||
+        url.split_once("://")
+            .is_some_and(|(scheme, _)| !scheme.contains(['/', '?', '#']))
+}
+
+/// Add a (normalized) base path to a URL of the site.
+///
+/// The URLs that aren't absolute paths, such as `https://...` or `//cdn.example.com/...`, are kept
+/// as is.
+pub fn prefix<'a>(base_path: &str, url: &'a str) -> Cow<'a, str> {
+    if base_path.is_empty() || !url.starts_with('/') || is_external(url) {
+        Cow::Borrowed(url)
+    } else if url == "/" {
+        Cow::Owned(base_path.to_owned())
+    } else {
+        Cow::Owned(format!("{base_path}{url}"))
+    }
+}
+
+/// Add the base path of `./wini.toml` to a URL of the site
+pub fn with_base_path(url: &str) -> Cow<'_, str> {
+    prefix(SERVER_CONFIG.base_path(), url)
+}
+
+/// Serve a router under the base path of `./wini.toml`.
+///
+/// `/docs/` is redirected to `/docs`, the route of the index page.
+pub fn nest(router: Router) -> Router {
+    let base_path = SERVER_CONFIG.base_path();
+
+    if base_path.is_empty() {
+        router
+    } else {
+        Router::new()
+            .route(
+                &format!("{base_path}/"),
+                get(async || Redirect::permanent(base_path)),
+            )
+            .nest(base_path, router)
+    }
+}
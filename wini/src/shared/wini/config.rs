@@ -1,5 +1,11 @@
 use {
-    super::{cache::CacheCategory, dependencies::normalize_relative_path, env::EnvType, ENV_TYPE},
+    super::{
+        ENV_TYPE,
+        base_path,
+        cache::CacheCategory,
+        dependencies::normalize_relative_path,
+        env::EnvType,
+    },
     crate::{
         concat_paths,
         shared::wini::err::ExitWithMessageIfErr,
@@ -20,8 +26,21 @@ pub static SERVER_CONFIG: LazyLock<Config> =
 #[derive(Debug, serde::Deserialize, Getters)]
 #[getset(get = "pub")]
 pub struct Config {
+    /// The sub-path under which the site is served (see [`super::base_path`])
+    #[serde(default, deserialize_with = "deserialize_base_path")]
+    base_path: String,
     path: ConfigPath,
     cache: Caches,
+    #[serde(default)]
+    security: ConfigSecurity,
+    #[serde(default)]
+    resource_hints: ConfigResourceHints,
+    #[serde(default)]
+    critical_css: ConfigCriticalCss,
+    #[serde(default)]
+    navigation: ConfigNavigation,
+    #[serde(default)]
+    session: ConfigSession,
 }
 
 impl Config {
@@ -31,6 +50,15 @@ impl Config {
     }
 }
 
+fn deserialize_base_path<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let base_path = String::deserialize(deserializer)?;
+
+    Ok(base_path::normalize(&base_path))
+}
+
 
 /// The paths of different important folders
 /// ConfigPath uses [`String`] instead of [`std::path::PathBuf`] becase we often use
@@ -127,29 +155,246 @@ impl Caches {
             .map(AsRef::as_ref)
     }
 
-    /// Verify that all the cache categories have a cache rule associated to them
-    pub fn verify_all_attributes(&self) {
-        for env in EnvType::iter() {
-            for cache_for in CacheCategory::iter() {
-                // Function category is only used by macros to know if you want to precompute
-                // #[cached] functions.
-                if cache_for != CacheCategory::Function &&
+    /// The cache categories without a cache rule, in all the environments
+    pub fn missing_rules(&self) -> Vec<(EnvType, CacheCategory)> {
+        EnvType::iter()
+            .flat_map(|env| CacheCategory::iter().map(move |cache_for| (env, cache_for)))
+            // Function category is only used by macros to know if you want to precompute
+            // #[cached] functions.
+            .filter(|&(env, cache_for)| {
+                cache_for != CacheCategory::Function &&
                     self.get_opt_with_env_type(env, cache_for).is_none()
-                {
-                    log::error!(
-                        "\
-                    The cache for {cache_for:#?} isn't defined in the environment {env:#?}.\n\
-                    Look at your cache definitions in `./wini.toml`\
-                        "
-                    );
-                    panic!("End of program")
-                }
-            }
+            })
+            .collect()
+    }
+}
+
+
+/// A configuration section that can be overwritten depending on the environment.
+///
+/// `<section>.default` is applied to every environment, and `<section>.xxxxxxx` (with *xxxxxxx*
+/// being a type of environment) overwrites the fields it defines if the server is running under
+/// that environment.
+#[derive(Debug, serde::Deserialize)]
+pub struct PerEnvironment<T> {
+    default: Option<T>,
+    #[serde(flatten)]
+    environments: HashMap<EnvType, T>,
+}
+
+impl<T> Default for PerEnvironment<T> {
+    fn default() -> Self {
+        Self {
+            default: None,
+            environments: HashMap::new(),
+        }
+    }
+}
+
+impl<T> PerEnvironment<T> {
+    /// Get a field of the configuration in the current environment
+    pub fn get<'a, U>(&'a self, field: impl Fn(&'a T) -> Option<U>) -> Option<U> {
+        self.get_with_env_type(*ENV_TYPE, field)
+    }
+
+    pub fn get_with_env_type<'a, U>(
+        &'a self,
+        env_type: EnvType,
+        field: impl Fn(&'a T) -> Option<U>,
+    ) -> Option<U> {
+        self.environments
+            .get(&env_type)
+            .and_then(&field)
+            .or_else(|| self.default.as_ref().and_then(field))
+    }
+}
+
+
+/// The resource hints options
+#[derive(Debug, Default, serde::Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct ConfigResourceHints {
+    /// Also send the hints of the page in a `Link` header
+    #[serde(default)]
+    link_header: bool,
+}
+
+
+/// The critical CSS options
+#[derive(Debug, serde::Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct ConfigCriticalCss {
+    /// Inline the critical style sheets and load the others asynchronously
+    #[serde(default)]
+    enabled: bool,
+    /// The maximum size (in bytes) of a style sheet to be inlined, if it's not named `critical.css`
+    #[serde(default = "ConfigCriticalCss::default_max_size")]
+    max_size: usize,
+}
+
+impl ConfigCriticalCss {
+    const fn default_max_size() -> usize {
+        4096
+    }
+}
+
+impl Default for ConfigCriticalCss {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_size: Self::default_max_size(),
+        }
+    }
+}
+
+
+/// The client-side navigation options
+#[derive(Debug, serde::Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct ConfigNavigation {
+    /// Navigate between the pages without reloading them
+    #[serde(default)]
+    enabled: bool,
+    /// Fetch the pages when their links are hovered or focused
+    #[serde(default = "ConfigNavigation::default_prefetch")]
+    prefetch: bool,
+}
+
+impl ConfigNavigation {
+    const fn default_prefetch() -> bool {
+        true
+    }
+}
+
+impl Default for ConfigNavigation {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            prefetch: Self::default_prefetch(),
         }
     }
 }
 
 
+/// The sessions options
+#[derive(Debug, serde::Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct ConfigSession {
+    /// Load the session of each request
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    store: SessionStoreKind,
+    /// The directory of the `file` store
+    #[serde(default = "ConfigSession::default_path")]
+    path: String,
+    /// The number of seconds a session is kept after its last change
+    #[serde(default = "ConfigSession::default_max_age")]
+    max_age: u64,
+}
+
+impl ConfigSession {
+    fn default_path() -> String {
+        "./sessions".to_owned()
+    }
+
+    const fn default_max_age() -> u64 {
+        // A week
+        7 * 24 * 60 * 60
+    }
+}
+
+impl Default for ConfigSession {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            store: SessionStoreKind::default(),
+            path: Self::default_path(),
+            max_age: Self::default_max_age(),
+        }
+    }
+}
+
+/// Where the data of the sessions is kept (see [`super::session`])
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStoreKind {
+    /// In the memory of the server
+    #[default]
+    Memory,
+    /// In a JSON file per session
+    File,
+}
+
+
+/// The security options
+#[derive(Debug, Default, serde::Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct ConfigSecurity {
+    csp: Option<PerEnvironment<ConfigCsp>>,
+    #[serde(default)]
+    headers: PerEnvironment<ConfigHeaders>,
+    #[serde(default)]
+    csrf: ConfigCsrf,
+}
+
+
+/// The CSRF protection options
+#[derive(Debug, Default, serde::Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct ConfigCsrf {
+    /// Reject the requests with an unsafe method that don't send the token of their session
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    mode: CsrfMode,
+    /// The paths, and their sub-paths, that aren't checked, such as webhooks
+    #[serde(default)]
+    exempt: Vec<String>,
+}
+
+/// Where the token of a session is kept (see [`super::csrf`])
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsrfMode {
+    /// Signed from the id of the session in a cookie, without keeping anything on the server
+    #[default]
+    DoubleSubmit,
+    /// On the server, with the id of the session in a cookie
+    Synchronizer,
+}
+
+
+/// The Content-Security-Policy options
+#[derive(Debug, serde::Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct ConfigCsp {
+    /// The policy. `'nonce-…'` is added to its `script-src` and `style-src` directives.
+    policy: Option<String>,
+    /// Send the policy with `Content-Security-Policy-Report-Only` instead
+    report_only: Option<bool>,
+}
+
+
+/// The security headers sent with every response.
+///
+/// A header that isn't defined uses its default value from
+/// `shared::wini::security_headers::DEFAULT_HEADERS`, and an empty string disables it.
+#[derive(Debug, serde::Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct ConfigHeaders {
+    strict_transport_security: Option<String>,
+    x_content_type_options: Option<String>,
+    referrer_policy: Option<String>,
+    permissions_policy: Option<String>,
+    x_frame_options: Option<String>,
+    cross_origin_opener_policy: Option<String>,
+    cross_origin_embedder_policy: Option<String>,
+    /// The headers that shouldn't be sent for a path and all its sub-paths
+    exemptions: Option<HashMap<String, Vec<String>>>,
+}
+
+
 #[derive(Debug)]
 pub enum TomlLoadingError {
     ConfigFileDoesntExists(&'static str),
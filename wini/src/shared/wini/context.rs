@@ -0,0 +1,137 @@
+//! A context scoped to a request, that the components can read without it being passed through
+//! every level of components.
+//!
+//! `utils::wini::context::context_middleware` runs each request in its own [`Context`], that
+//! already contains the [`Uri`] of the request. The middlewares, the layouts and the pages add
+//! values to it with [`provide`], and the components read them with [`ctx`]:
+//!
+//! ```rust,ignore
+//! #[derive(Clone)]
+//! pub struct CurrentUser {
+//!     pub name: String,
+//! }
+//!
+//! // A middleware inside of `context_middleware`
+//! pub async fn current_user(session: Session, req: Request, next: Next) -> Response {
+//!     if let Some(name) = session.get::<String>("user") {
+//!         provide(CurrentUser { name });
+//!     }
+//!
+//!     next.run(req).await
+//! }
+//!
+//! #[component]
+//! pub async fn greeting() -> Markup {
+//!     html! {
+//!         @if let Some(user) = ctx::<CurrentUser>() {
+//!             p { "Hello " (user.name) "!" }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! The context is a task-local: it's kept across the `.await` points, but not in the tasks spawned
+//! with `tokio::spawn`, that must run in [`Context::scope`] to have it.
+//!
+//! The pages rendered statically get the values of `SsgRouter::context` instead of the ones of a
+//! real request, such as an anonymous user.
+
+use {
+    hyper::Uri,
+    std::{
+        any::{Any, TypeId},
+        collections::HashMap,
+        sync::{Arc, Mutex, PoisonError},
+    },
+};
+
+tokio::task_local! {
+    /// The context of the current request
+    static CONTEXT: Context;
+}
+
+type Values = HashMap<TypeId, Arc<dyn Any + Send + Sync>>;
+
+
+/// The values of a request, one per type.
+///
+/// It's cheap to clone, and the clones share the same values.
+#[derive(Clone, Default)]
+pub struct Context(Arc<Mutex<Values>>);
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The context of the current request, if there is one
+    pub fn current() -> Option<Self> {
+        CONTEXT.try_with(Clone::clone).ok()
+    }
+
+    /// The context of a request to `uri`
+    pub fn for_uri(uri: Uri) -> Self {
+        Self::new().with(uri)
+    }
+
+    /// Add a value to the context
+    pub fn with<T: Any + Send + Sync>(self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    /// Add a value to the context, replacing the one of the same type
+    pub fn insert<T: Any + Send + Sync>(&self, value: T) {
+        self.values().insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// Add all the values of another context, replacing the ones of the same types
+    pub fn extend(&self, other: &Self) {
+        if Arc::ptr_eq(&self.0, &other.0) {
+            return;
+        }
+
+        let other = other.values().clone();
+        self.values().extend(other);
+    }
+
+    /// The value of a type
+    pub fn get<T: Any + Send + Sync + Clone>(&self) -> Option<T> {
+        self.values()
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+    }
+
+    /// Run a future in this context
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        CONTEXT.scope(self, f).await
+    }
+
+    fn values(&self) -> std::sync::MutexGuard<'_, Values> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl std::fmt::Debug for Context {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Context")
+            .field("values", &self.values().len())
+            .finish()
+    }
+}
+
+
+/// The value of a type in the context of the current request.
+///
+/// `None` if it wasn't provided, or outside of a request.
+pub fn ctx<T: Any + Send + Sync + Clone>() -> Option<T> {
+    CONTEXT.try_with(Context::get::<T>).ok().flatten()
+}
+
+/// Add a value to the context of the current request, for the components rendered after.
+///
+/// Returns `false` outside of a request, where the value is dropped.
+pub fn provide<T: Any + Send + Sync>(value: T) -> bool {
+    CONTEXT.try_with(|context| context.insert(value)).is_ok()
+}
@@ -0,0 +1,41 @@
+//! The cookies of the server, such as the ones of the CSRF protection and of the sessions.
+
+use {
+    super::{ENV_TYPE, base_path::with_base_path, env::EnvType},
+    axum::http::HeaderMap,
+    hyper::header::COOKIE,
+};
+
+
+/// The value of a cookie of a request
+pub fn get<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| {
+            let (cookie_name, value) = pair.trim().split_once('=')?;
+            (cookie_name == name).then_some(value)
+        })
+}
+
+/// A `Set-Cookie` for the whole site, not readable by scripts and only sent over HTTPS outside of
+/// the local environment.
+///
+/// Without `max_age`, the cookie is removed when the browser is closed.
+pub fn set(name: &str, value: &str, max_age: Option<u64>) -> String {
+    let max_age = max_age
+        .map(|max_age| format!("; Max-Age={max_age}"))
+        .unwrap_or_default();
+    let secure = if *ENV_TYPE == EnvType::Local {
+        ""
+    } else {
+        "; Secure"
+    };
+
+    format!(
+        "{name}={value}; Path={}; HttpOnly; SameSite=Lax{max_age}{secure}",
+        with_base_path("/")
+    )
+}
@@ -0,0 +1,98 @@
+//! Content-Security-Policy nonces.
+//!
+//! A new nonce is generated for each request by `utils::wini::csp::csp_middleware`. It's added
+//! by `template::template` to every `<script>` and `<link>` tag it renders, and can be extracted
+//! by pages, layouts and components that need inline scripts or styles.
+//!
+//! With a policy, the HTML responses are private (`Cache-Control: private, no-store`), so that a
+//! nonce is never sent twice. The nonces are removed from the pages generated by the SSG, that are
+//! served as is to every client.
+
+use {
+    crate::shared::wini::err::{ServerError, ServerErrorKind},
+    axum::{extract::FromRequestParts, http::request::Parts},
+    base64::{Engine, engine::general_purpose::STANDARD},
+    maud::Render,
+    std::{fmt::Display, sync::Arc},
+};
+
+/// The directives that receive the nonce of the request
+const NONCE_DIRECTIVES: [&str; 2] = ["script-src", "style-src"];
+
+
+/// The nonce of the current request.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[page]
+/// pub async fn render(nonce: CspNonce) -> Markup {
+///     html! {
+///         script nonce=(nonce) { (PreEscaped("console.log('Hello world!')")) }
+///     }
+/// }
+/// ```
+///
+/// > Note that the nonce changes with every request: markup including it shouldn't be `#[cached]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CspNonce(Arc<str>);
+
+impl CspNonce {
+    /// Generate a new random nonce
+    pub fn generate() -> Self {
+        let bytes: [u8; 16] = rand::random();
+        Self(STANDARD.encode(bytes).into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for CspNonce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Render for CspNonce {
+    fn render_to(&self, buffer: &mut String) {
+        self.as_str().render_to(buffer);
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for CspNonce {
+    type Rejection = ServerError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<CspNonce>().cloned().ok_or_else(|| {
+            ServerErrorKind::DebugedError(
+                "No CSP nonce in the request. Is `csp_middleware` used on this route?".to_owned(),
+            )
+            .into()
+        })
+    }
+}
+
+
+/// Add the nonce to the `script-src` and `style-src` directives of a policy.
+///
+/// Directives that aren't present in the policy aren't added, so that they keep falling back on
+/// `default-src`.
+pub fn policy_with_nonce(policy: &str, nonce: &CspNonce) -> String {
+    policy
+        .split(';')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .map(|directive| {
+            let name = directive.split_whitespace().next().unwrap_or_default();
+
+            if NONCE_DIRECTIVES.contains(&name) {
+                format!("{directive} 'nonce-{nonce}'")
+            } else {
+                directive.to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
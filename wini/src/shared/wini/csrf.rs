@@ -0,0 +1,389 @@
+//! Protection against cross-site request forgery, configured in `[security.csrf]` of `./wini.toml`.
+//!
+//! `utils::wini::csrf::csrf_middleware` gives a token to each session, and rejects the requests
+//! with an unsafe method (`POST`, `PUT`, `PATCH`, `DELETE`, ...) that don't send it back:
+//! - in the `_csrf` field of a form, urlencoded or `multipart/form-data`. It's added to the
+//!   `method="post"` forms of the HTML responses that post to the site, such as the ones of an
+//!   `#[action]`, by [`add_token_to_forms`], and it can be rendered by [`csrf_input`],
+//! - or in the `X-CSRF-Token` header. `./public/helpers.js` adds it to the requests of htmx and of
+//!   `api`, from the `<meta name="csrf-token">` added by the template.
+//!
+//! The session is identified by a cookie holding its id. Its token depends on the mode:
+//! - `double_submit`: the HMAC of the id, with the `SESSION_SECRET` of `./.env`. Nothing is kept by
+//!   the server, so the session of a new client is created by the first page it loads.
+//! - `synchronizer`: a random token kept by the server. The session is only created when its token
+//!   is read, by [`CsrfToken`]: a page sending requests from JavaScript, with the
+//!   `<meta name="csrf-token">`, must read it for the first requests of a client.
+//!
+//! The responses carrying a token, or the cookie of a new session, are private to their client:
+//! they get `Cache-Control: private, no-store` and `Vary: Cookie`.
+//!
+//! The requests authenticated with an `Authorization: Bearer` header, and the `exempt` paths,
+//! aren't checked: browsers don't add them by themselves to cross-site requests.
+
+use {
+    crate::shared::wini::{
+        base_path::is_external,
+        config::{ConfigCsrf, CsrfMode},
+        cookie,
+        err::{ServerError, ServerErrorKind},
+        session,
+    },
+    axum::{
+        extract::FromRequestParts,
+        http::{HeaderMap, Method, request::Parts},
+    },
+    base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD},
+    cached::{Cached, TimedSizedCache},
+    hyper::header::AUTHORIZATION,
+    maud::{Markup, Render, html},
+    regex::Regex,
+    std::{
+        fmt::Display,
+        sync::{Arc, LazyLock, Mutex, OnceLock, PoisonError},
+        time::Duration,
+    },
+};
+
+/// The field of the forms containing the token
+pub const CSRF_FIELD: &str = "_csrf";
+
+/// The header containing the token, for the requests that aren't forms
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// The cookie identifying the session
+pub const CSRF_COOKIE: &str = "wini_csrf";
+
+/// A form, with the attributes of its opening tag and its content
+static FORM_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<form\b([^>]*)>(.*?)</form").expect("valid regex"));
+
+/// The `method` and the `action` of a form
+static FORM_ATTRIBUTE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b(method|action)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#)
+        .expect("valid regex")
+});
+
+/// The tokens of the sessions, in the `synchronizer` mode
+static SESSIONS: LazyLock<Mutex<TimedSizedCache<String, CsrfToken>>> = LazyLock::new(|| {
+    // The sessions that are still used are kept, the others expire after a day
+    Mutex::new(TimedSizedCache::with_size_and_lifespan_and_refresh(
+        100_000,
+        Duration::from_secs(24 * 60 * 60),
+        true,
+    ))
+});
+
+
+/// The CSRF token of the session of the current request.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[page]
+/// pub async fn render(csrf: CsrfToken) -> Markup {
+///     html! {
+///         form method="post" {
+///             [csrf_input(&csrf)]
+///             button { "Delete" }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrfToken(Arc<str>);
+
+impl CsrfToken {
+    /// Generate a new random token
+    pub fn generate() -> Self {
+        let bytes: [u8; 32] = rand::random();
+        Self(URL_SAFE_NO_PAD.encode(bytes).into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether a token sent by the client is this one, in constant time
+    pub fn matches(&self, sent: &str) -> bool {
+        self.0.len() == sent.len() &&
+            self.0
+                .bytes()
+                .zip(sent.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b)) ==
+                0
+    }
+
+    /// The token of a session in the `double_submit` mode, that can't be made without the secret
+    pub fn signed(session_id: &str) -> Self {
+        Self(session::signature(&format!("csrf.{session_id}")).into())
+    }
+}
+
+impl Display for CsrfToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Render for CsrfToken {
+    fn render_to(&self, buffer: &mut String) {
+        self.as_str().render_to(buffer);
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for CsrfToken {
+    type Rejection = ServerError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CsrfSession>()
+            .map(CsrfSession::token)
+            .ok_or_else(|| {
+                ServerErrorKind::DebugedError(
+                    "No CSRF token in the request. Is `[security.csrf]` enabled in `./wini.toml`?"
+                        .to_owned(),
+                )
+                .into()
+            })
+    }
+}
+
+
+/// The session of a request, that is created when its token is read if the client doesn't have one
+#[derive(Debug, Clone)]
+pub struct CsrfSession(Arc<CsrfSessionState>);
+
+#[derive(Debug)]
+struct CsrfSessionState {
+    mode: CsrfMode,
+    token: OnceLock<CsrfToken>,
+    /// The cookie to set, if the session was created by this request
+    set_cookie: OnceLock<String>,
+}
+
+impl CsrfSession {
+    /// The session of the cookie of a request, if it's valid
+    pub fn from_headers(headers: &HeaderMap, mode: CsrfMode) -> Self {
+        let token = OnceLock::new();
+
+        if let Some(session_id) = cookie::get(headers, CSRF_COOKIE) &&
+            let Some(existing) = match mode {
+                CsrfMode::DoubleSubmit => {
+                    is_session_id(session_id).then(|| CsrfToken::signed(session_id))
+                },
+                CsrfMode::Synchronizer => {
+                    SESSIONS
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .cache_get(session_id)
+                        .cloned()
+                },
+            }
+        {
+            let _ = token.set(existing);
+        }
+
+        Self(Arc::new(CsrfSessionState {
+            mode,
+            token,
+            set_cookie: OnceLock::new(),
+        }))
+    }
+
+    /// The token of the session, that is created if the client doesn't have one
+    pub fn token(&self) -> CsrfToken {
+        self.0
+            .token
+            .get_or_init(|| {
+                let session_id = CsrfToken::generate().to_string();
+                let token = match self.0.mode {
+                    CsrfMode::DoubleSubmit => CsrfToken::signed(&session_id),
+                    CsrfMode::Synchronizer => {
+                        let token = CsrfToken::generate();
+                        SESSIONS
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .cache_set(session_id.clone(), token.clone());
+                        token
+                    },
+                };
+
+                let _ = self
+                    .0
+                    .set_cookie
+                    .set(cookie::set(CSRF_COOKIE, &session_id, None));
+
+                token
+            })
+            .clone()
+    }
+
+    /// The token of the session, if the client has one or if it was created
+    pub fn existing_token(&self) -> Option<&CsrfToken> {
+        self.0.token.get()
+    }
+
+    /// The cookie to set, if the session was created by this request
+    pub fn set_cookie(&self) -> Option<&str> {
+        self.0.set_cookie.get().map(String::as_str)
+    }
+}
+
+
+/// The hidden field sending the token with a form
+pub async fn csrf_input(token: &CsrfToken) -> Markup {
+    html! {
+        input type="hidden" name=(CSRF_FIELD) value=(token);
+    }
+}
+
+
+/// Add the hidden field of the token to the `method="post"` forms of some HTML that post to the
+/// site, and that don't have it yet. `None` if there isn't any.
+///
+/// `token` is only called if a form needs it, so that a session isn't created for nothing.
+pub fn add_token_to_forms(html: &str, token: impl FnOnce() -> CsrfToken) -> Option<String> {
+    let mut token = Some(token);
+    let mut input = String::new();
+    let mut with_tokens = String::with_capacity(html.len());
+    let mut end = 0;
+
+    for form in FORM_REGEX.captures_iter(html) {
+        let (Some(attributes), Some(content)) = (form.get(1), form.get(2)) else {
+            continue;
+        };
+
+        let (mut method, mut action) = ("get", "");
+        for attribute in FORM_ATTRIBUTE_REGEX.captures_iter(attributes.as_str()) {
+            let value = (2..=4)
+                .find_map(|group| attribute.get(group))
+                .map_or("", |value| value.as_str());
+            if attribute[1].eq_ignore_ascii_case("method") {
+                method = value;
+            } else {
+                action = value;
+            }
+        }
+
+        // The token mustn't be sent to another site
+        if !method.eq_ignore_ascii_case("post") ||
+            is_external(action) ||
+            content
+                .as_str()
+                .contains(&format!(r#"name="{CSRF_FIELD}""#))
+        {
+            continue;
+        }
+
+        if let Some(token) = token.take() {
+            input = format!(
+                r#"<input type="hidden" name="{CSRF_FIELD}" value="{}">"#,
+                token()
+            );
+        }
+
+        // After the `>` of the opening tag
+        let insert_at = attributes.end() + 1;
+        with_tokens.push_str(&html[end..insert_at]);
+        with_tokens.push_str(&input);
+        end = insert_at;
+    }
+
+    (end > 0).then(|| {
+        with_tokens.push_str(&html[end..]);
+        with_tokens
+    })
+}
+
+/// Whether the value of a cookie could be an id generated by [`CsrfToken::generate`]
+fn is_session_id(value: &str) -> bool {
+    value.len() == 43 &&
+        value
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+}
+
+/// Whether a request can't change anything, and so doesn't need a token
+pub fn is_safe_method(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    )
+}
+
+/// Whether a request isn't checked
+pub fn is_exempt(path: &str, headers: &HeaderMap, config: &ConfigCsrf) -> bool {
+    let has_bearer_token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .get(..7)
+                .is_some_and(|scheme| scheme.eq_ignore_ascii_case("bearer "))
+        });
+
+    has_bearer_token ||
+        config.exempt().iter().any(|exempt| {
+            let exempt = exempt.trim_end_matches('/');
+            path == exempt ||
+                path.strip_prefix(exempt)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+}
+
+/// The token in the `_csrf` field of a form
+pub fn token_from_form(body: &[u8]) -> Option<String> {
+    serde_urlencoded::from_bytes::<Vec<(String, String)>>(body)
+        .ok()?
+        .into_iter()
+        .find_map(|(name, value)| (name == CSRF_FIELD).then_some(value))
+}
+
+/// The token in the `_csrf` field of a `multipart/form-data` form, with the boundary of its
+/// `content_type`
+pub fn token_from_multipart(content_type: &str, body: &[u8]) -> Option<String> {
+    let boundary = content_type.split(';').find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("boundary")
+            .then(|| value.trim().trim_matches('"'))
+    })?;
+    let boundary = format!("--{boundary}");
+    let delimiter = format!("\r\n{boundary}");
+
+    // The first delimiter doesn't follow a line break
+    let mut rest = body.strip_prefix(boundary.as_bytes())?;
+
+    loop {
+        let end = find(rest, delimiter.as_bytes())?;
+        let (part, after) = rest.split_at(end);
+        rest = after.get(delimiter.len()..)?;
+
+        let headers_end = find(part, b"\r\n\r\n")?;
+        let (headers, content) = part.split_at(headers_end);
+
+        let is_token = std::str::from_utf8(headers)
+            .ok()?
+            .split("\r\n")
+            .any(|header| {
+                header.split_once(':').is_some_and(|(name, value)| {
+                    name.trim().eq_ignore_ascii_case("content-disposition") &&
+                        value.split(';').any(|parameter| {
+                            parameter.trim() == format!(r#"name="{CSRF_FIELD}""#)
+                        })
+                })
+            });
+        if is_token {
+            return String::from_utf8(content.get(4..)?.to_vec()).ok();
+        }
+    }
+}
+
+/// The position of the first `needle` in `haystack`
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
@@ -1,12 +1,17 @@
 use {
-    axum::response::{IntoResponse, Response},
+    crate::shared::wini::{ENV_TYPE, env::EnvType, problem_details::ProblemDetails},
+    axum::{
+        http::HeaderValue,
+        response::{IntoResponse, Response},
+    },
     hyper::{
-        header::{InvalidHeaderValue, ToStrError},
         StatusCode,
+        header::{InvalidHeaderValue, ToStrError},
     },
     maud::Markup,
     std::{
         convert::Infallible,
+        error::Error,
         fmt::{self, Display},
         str::Utf8Error,
         string::FromUtf8Error,
@@ -16,6 +21,14 @@ use {
 
 pub type ServerResult<T> = Result<T, ServerError>;
 
+/// The error of the pages, components, layouts and middlewares.
+///
+/// Every [`Error`] is converted into it with `?` (see [`HttpError`]). Because of this conversion, it
+/// doesn't implement [`Error`] itself, like `anyhow::Error`: it would conflict with the conversion of
+/// a type into itself. It can be converted into a `Box<dyn Error + Send + Sync>` instead.
+///
+/// `StatusCode` and `String` can't be converted with `?` either, since they could implement
+/// [`Error`] in the future: use [`ServerError::status`] and [`ServerError::other`].
 #[derive(Debug)]
 pub struct ServerError {
     kind: ServerErrorKind,
@@ -30,9 +43,30 @@ impl Display for ServerError {
     }
 }
 
-impl std::error::Error for ServerError {}
-
 impl ServerError {
+    /// An error responding with a status code, such as `StatusCode::NOT_FOUND`
+    pub fn status(status_code: StatusCode) -> Self {
+        ServerErrorKind::Status(status_code).into()
+    }
+
+    /// Create an error from any boxable error, including a `String` or a `&str` message, such as an `anyhow::Error` or a
+    /// `Box<dyn Error + Send + Sync>`, which can't be converted with `?`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let user = fetch_user(id).await.map_err(ServerError::other)?;
+    /// ```
+    pub fn other(err: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        ServerErrorKind::Other(Box::new(BoxedError(err.into()))).into()
+    }
+
+    /// The response of the error in an environment, see [`ServerErrorKind::to_response_with_trace`]
+    pub fn to_response(&self, env_type: EnvType) -> Response {
+        self.kind
+            .to_response_with_trace(env_type, self.trace.as_deref())
+    }
+
     pub fn add_trace(&mut self, trace: Trace) {
         match &mut self.trace {
             Some(curr_trace) => {
@@ -55,6 +89,8 @@ pub enum ServerErrorKind {
     DebugedError(String),
     PublicRessourceNotFound(String),
     ToStrError(ToStrError),
+    /// Any other error, with its source chain
+    Other(Box<dyn HttpError>),
 }
 
 impl From<ServerErrorKind> for ServerError {
@@ -83,19 +119,135 @@ macro_rules! impl_from_error {
     };
 }
 
-impl_from_error!(hyper::StatusCode, ServerErrorKind::Status);
 impl_from_error!(Infallible, ServerErrorKind::Infallible);
 impl_from_error!(FromUtf8Error, ServerErrorKind::FromUtf8Error);
 impl_from_error!(Utf8Error, ServerErrorKind::Utf8Error);
-impl_from_error!(String, ServerErrorKind::DebugedError);
 impl_from_error!(InvalidHeaderValue, ServerErrorKind::InvalidHeader);
 impl_from_error!(ToStrError, ServerErrorKind::ToStrError);
 
+// `StatusCode` and `String` can't be converted with `?`: they would conflict with the conversion
+// of all the errors, as they could implement `Error` in the future. See `ServerError::status` and
+// `ServerError::other`.
+impl<E: Error + Send + Sync + 'static> From<E> for ServerError {
+    default fn from(err: E) -> Self {
+        ServerErrorKind::Other(Box::new(err)).into()
+    }
+}
 
-impl IntoResponse for &ServerErrorKind {
-    fn into_response(self) -> Response {
-        eprintln!("{self:#?}");
-        let err_msg = match self {
+impl<E: Error + Send + Sync + 'static> From<E> for ServerErrorKind {
+    default fn from(err: E) -> Self {
+        ServerErrorKind::Other(Box::new(err))
+    }
+}
+
+
+/// An error returned by a page, a component or a layout.
+///
+/// All the types implementing [`Error`] implement it, and are internal server errors without a
+/// public message. Implement it for your own errors to choose the status code of the response and
+/// the message sent to the client.
+///
+/// Such an implementation specializes the one of all the errors, so the crate needs
+/// `#![feature(specialization)]`, which `./src/lib.rs` already enables.
+///
+/// # Example
+///
+/// ```ignore
+/// impl HttpError for UserNotFound {
+///     fn status_code(&self) -> StatusCode {
+///         StatusCode::NOT_FOUND
+///     }
+///
+///     fn public_message(&self) -> Option<String> {
+///         Some(format!("There is no user named {}", self.0))
+///     }
+/// }
+/// ```
+pub trait HttpError: Error + Send + Sync + 'static {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    /// The message that can be sent to the client in all the environments
+    fn public_message(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<E: Error + Send + Sync + 'static> HttpError for E {
+    default fn status_code(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    default fn public_message(&self) -> Option<String> {
+        None
+    }
+}
+
+impl From<ServerError> for Box<dyn Error + Send + Sync> {
+    fn from(err: ServerError) -> Self {
+        Box::new(ServerErrorAsError(err))
+    }
+}
+
+/// A [`ServerError`], implementing [`Error`] once it can't be converted back with `?`
+#[derive(Debug)]
+struct ServerErrorAsError(ServerError);
+
+impl Display for ServerErrorAsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Error for ServerErrorAsError {}
+
+/// A boxed error, which doesn't implement [`Error`] itself
+#[derive(Debug)]
+struct BoxedError(Box<dyn Error + Send + Sync>);
+
+impl Display for BoxedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Error for BoxedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
+
+
+/// The header containing the [`ErrorId`] of an error response
+pub const ERROR_ID_HEADER: &str = "x-error-id";
+
+/// Identifier of an error, sent to the client and logged with the error so that a report from a
+/// user can be matched with the logs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorId(Arc<str>);
+
+impl ErrorId {
+    pub fn generate() -> Self {
+        Self(format!("{:016x}", rand::random::<u64>()).into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for ErrorId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+
+impl ServerErrorKind {
+    /// The message describing the error, with its internal details
+    fn internal_message(&self) -> String {
+        match self {
             ServerErrorKind::InvalidHeader(err) => {
                 format!("Unexpected header value: {err}")
             },
@@ -115,13 +267,108 @@ impl IntoResponse for &ServerErrorKind {
                 format!("Error decoding buffer to UTF-8: {err:#?}")
             },
             ServerErrorKind::PublicRessourceNotFound(path) => {
-                return (StatusCode::NOT_FOUND, format!("Couldn't find file: {path}"))
-                    .into_response();
+                format!("Couldn't find file: {path}")
+            },
+            ServerErrorKind::Status(status_code) => status_code.to_string(),
+            ServerErrorKind::Other(err) => {
+                let mut message = format!("Unexpected error: {err}");
+                let mut source = err.source();
+
+                while let Some(err) = source {
+                    message.push_str(&format!("\nCaused by: {err}"));
+                    source = err.source();
+                }
+
+                message
+            },
+        }
+    }
+
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ServerErrorKind::Status(status_code) => *status_code,
+            ServerErrorKind::PublicRessourceNotFound(_) => StatusCode::NOT_FOUND,
+            ServerErrorKind::Other(err) => err.status_code(),
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Create the response of the error in an environment.
+    ///
+    /// Unexpected errors are logged with their trace under a new [`ErrorId`]. Their details are
+    /// only sent to the client in local and dev environments.
+    ///
+    /// The [`ProblemDetails`] of the error are added to the extensions of the response, to send
+    /// them to the clients that prefer JSON.
+    pub fn to_response_with_trace(&self, env_type: EnvType, trace: Option<&[Trace]>) -> Response {
+        let status_code = self.status_code();
+
+        if let ServerErrorKind::Status(status_code) = self {
+            return status_code.into_response();
+        }
+
+        // Errors of the client don't need to be investigated
+        if !status_code.is_server_error() {
+            let detail = match self {
+                ServerErrorKind::PublicRessourceNotFound(_) => Some(self.internal_message()),
+                ServerErrorKind::Other(err) => err.public_message(),
+                _ => None,
+            };
+
+            let mut resp = match &detail {
+                Some(detail) => (status_code, detail.clone()).into_response(),
+                None => status_code.into_response(),
+            };
+            resp.extensions_mut().insert(ProblemDetails {
+                detail,
+                ..ProblemDetails::new(status_code)
+            });
+
+            return resp;
+        }
+
+        let error_id = ErrorId::generate();
+
+        log::error!(
+            "error_id={error_id} error={self:?} trace={:?}",
+            trace_on_one_line(trace.unwrap_or_default()),
+        );
+
+        let detail = match env_type {
+            EnvType::Local | EnvType::Dev => self.internal_message(),
+            EnvType::Prod | EnvType::Staging => {
+                match self {
+                    ServerErrorKind::Other(err) => err.public_message(),
+                    _ => None,
+                }
+                .unwrap_or_else(|| "An unexpected error occurred.".to_owned())
             },
-            ServerErrorKind::Status(status_code) => return status_code.into_response(),
         };
 
-        (StatusCode::INTERNAL_SERVER_ERROR, err_msg).into_response()
+        let body = match env_type {
+            EnvType::Local | EnvType::Dev => detail.clone(),
+            EnvType::Prod | EnvType::Staging => format!("{detail} Error ID: {error_id}"),
+        };
+
+        let mut resp = (status_code, body).into_response();
+
+        if let Ok(header_value) = HeaderValue::from_str(error_id.as_str()) {
+            resp.headers_mut().insert(ERROR_ID_HEADER, header_value);
+        }
+        resp.extensions_mut().insert(ProblemDetails {
+            detail: Some(detail),
+            error_id: Some(error_id.to_string()),
+            ..ProblemDetails::new(status_code)
+        });
+        resp.extensions_mut().insert(error_id);
+
+        resp
+    }
+}
+
+impl IntoResponse for &ServerErrorKind {
+    fn into_response(self) -> Response {
+        self.to_response_with_trace(*ENV_TYPE, None)
     }
 }
 
@@ -133,13 +380,13 @@ impl IntoResponse for ServerErrorKind {
 
 impl IntoResponse for &ServerError {
     fn into_response(self) -> Response {
-        (&self.kind).into_response()
+        self.to_response(*ENV_TYPE)
     }
 }
 
 impl IntoResponse for ServerError {
     fn into_response(self) -> Response {
-        self.kind.into_response()
+        (&self).into_response()
     }
 }
 
@@ -179,19 +426,17 @@ where
     E: std::fmt::Debug,
 {
     fn exit_with_msg_if_err(self, msg: impl std::fmt::Display) -> T {
-        self.map_err(|err| {
+        self.unwrap_or_else(|err| {
             log::error!("{msg}: {err:?}");
-            panic!("End of program")
+            std::process::exit(1)
         })
-        .expect("Already exited if `Err`")
     }
 
     fn exit_with_msg_to_compute_if_err<S: std::fmt::Display, F: Fn() -> S>(self, msg: F) -> T {
-        self.map_err(|err| {
+        self.unwrap_or_else(|err| {
             log::error!("{}: {err:?}", msg());
-            panic!("End of program")
+            std::process::exit(1)
         })
-        .unwrap()
     }
 }
 
@@ -201,6 +446,8 @@ pub struct Backtrace {
     pub err: Arc<ServerErrorKind>,
     // First element is the oldest
     pub trace: Vec<Trace>,
+    /// The identifier under which the error was logged, if it was
+    pub error_id: Option<ErrorId>,
 }
 
 #[derive(Debug, Clone)]
@@ -215,10 +462,20 @@ impl From<ServerError> for Backtrace {
             markup: None,
             err: Arc::new(value.kind),
             trace: value.trace.unwrap_or_default(),
+            error_id: None,
         }
     }
 }
 
+/// `src/components/a.rs::a > src/pages/b.rs::b`
+pub fn trace_on_one_line(trace: &[Trace]) -> String {
+    trace
+        .iter()
+        .map(|trace| format!("{}::{}", trace.file_path, trace.function_name))
+        .collect::<Vec<_>>()
+        .join(" > ")
+}
+
 fn write_traces(traces: &Option<Vec<Trace>>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     let Some(traces) = &traces else {
         return Ok(());
@@ -0,0 +1,112 @@
+//! The registry of the error pages, filled by `#[error_page]`.
+
+use {
+    crate::shared::wini::err::Backtrace,
+    hyper::StatusCode,
+    maud::Markup,
+    std::{
+        collections::{BTreeMap, btree_map::Entry},
+        pin::Pin,
+        sync::{Mutex, PoisonError},
+    },
+};
+
+pub type ErrorPageFuture = Pin<Box<dyn Future<Output = Markup> + Send>>;
+pub type ErrorPage = fn(StatusCode, Option<Backtrace>) -> ErrorPageFuture;
+
+/// The error pages of the program
+static ERROR_PAGES: Mutex<ErrorPages> = Mutex::new(ErrorPages::new());
+
+
+/// An error page, with where it's defined
+#[derive(Debug, Clone, Copy)]
+pub struct RegisteredErrorPage {
+    pub page: ErrorPage,
+    /// The path of its function
+    pub name: &'static str,
+    /// The file defining it
+    pub file: &'static str,
+}
+
+/// The error pages per status code. `None` is the page used for all the other errors.
+#[derive(Debug, Default)]
+pub struct ErrorPages {
+    pages: BTreeMap<Option<u16>, RegisteredErrorPage>,
+    /// The pages registered for a status code that already had one, which aren't used
+    duplicates: Vec<(Option<u16>, RegisteredErrorPage)>,
+}
+
+impl ErrorPages {
+    pub const fn new() -> Self {
+        Self {
+            pages: BTreeMap::new(),
+            duplicates: Vec::new(),
+        }
+    }
+
+    /// Register an error page. Only the first page of a status code is used, the others are
+    /// reported by [`Self::problems`].
+    pub fn register(&mut self, status_code: Option<u16>, page: RegisteredErrorPage) {
+        match self.pages.entry(status_code) {
+            Entry::Vacant(entry) => {
+                entry.insert(page);
+            },
+            Entry::Occupied(_) => self.duplicates.push((status_code, page)),
+        }
+    }
+
+    /// Find the error page for a status code
+    pub fn get(&self, status_code: StatusCode) -> Option<ErrorPage> {
+        self.pages
+            .get(&Some(status_code.as_u16()))
+            .or_else(|| self.pages.get(&None))
+            .map(|registered| registered.page)
+    }
+
+    /// The file and the message of the status codes with multiple error pages
+    pub fn problems(&self) -> Vec<(&'static str, String)> {
+        self.duplicates
+            .iter()
+            .filter_map(|(status_code, duplicate)| {
+                let first = self.pages.get(status_code)?;
+                let status_codes = match status_code {
+                    Some(status_code) => status_code.to_string(),
+                    None => "all the status codes".to_owned(),
+                };
+
+                Some((
+                    duplicate.file,
+                    format!(
+                        "Multiple error pages registered for {status_codes}: `{}` and `{}`",
+                        first.name, duplicate.name
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+
+/// Register an error page. Used by `#[error_page]`.
+pub fn register(status_code: Option<u16>, page: RegisteredErrorPage) {
+    ERROR_PAGES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .register(status_code, page);
+}
+
+/// Find the error page for a status code
+pub fn get(status_code: StatusCode) -> Option<ErrorPage> {
+    ERROR_PAGES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(status_code)
+}
+
+/// The file and the message of the status codes with multiple error pages, reported at startup
+pub fn problems() -> Vec<(&'static str, String)> {
+    ERROR_PAGES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .problems()
+}
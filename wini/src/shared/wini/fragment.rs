@@ -0,0 +1,118 @@
+//! Partial responses, for the requests that only replace a part of the current page, such as the
+//! ones of htmx.
+//!
+//! A request is a fragment request if it has:
+//! - a `?fragment=<name>` query,
+//! - a `X-Wini-Fragment: <name>` header,
+//! - a `HX-Request` header, except for the boosted links and forms of htmx (`HX-Boosted`), that
+//!   replace the whole page.
+//!
+//! The page is then sent without its layouts and the template: only its body, or only the
+//! [`fragment`] named `<name>` if there is one. The style sheets and the scripts of the page are
+//! added before it with `<link>` and `<script>` tags.
+
+use {
+    hyper::HeaderMap,
+    maud::{Markup, PreEscaped},
+};
+
+/// The header of the fragment requests
+pub const FRAGMENT_HEADER: &str = "x-wini-fragment";
+
+/// The query parameter of the fragment requests
+pub const FRAGMENT_QUERY: &str = "fragment";
+
+
+/// The part of the page requested
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fragment {
+    /// The body of the page, without the layouts
+    Body,
+    /// A [`fragment`] of the page
+    Named(String),
+}
+
+impl Fragment {
+    /// The fragment requested, if it's a fragment request
+    pub fn from_request(headers: &HeaderMap, query: Option<&str>) -> Option<Self> {
+        let from_query = query.and_then(|query| {
+            query.split('&').find_map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (name == FRAGMENT_QUERY).then(|| {
+                    percent_encoding::percent_decode_str(value)
+                        .decode_utf8_lossy()
+                        .into_owned()
+                })
+            })
+        });
+
+        let from_header = || {
+            headers
+                .get(FRAGMENT_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned)
+        };
+
+        let is_htmx = || headers.contains_key("hx-request") && !headers.contains_key("hx-boosted");
+
+        match from_query.or_else(from_header) {
+            Some(name) if name.trim().is_empty() => Some(Self::Body),
+            Some(name) => Some(Self::Named(name.trim().to_owned())),
+            None => is_htmx().then_some(Self::Body),
+        }
+    }
+}
+
+
+/// Mark a part of a page that can be requested alone, with `?fragment=<name>` or
+/// `X-Wini-Fragment: <name>`.
+///
+/// The markers are HTML comments, so they don't change the rendering of the full page.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use PROJECT_NAME_TO_RESOLVE::shared::wini::fragment::fragment;
+///
+/// #[page]
+/// pub async fn post() -> Markup {
+///     html! {
+///         article { "..." }
+///         [fragment("comments", html! {
+///             ul #comments { li { "First!" } }
+///         })]
+///         button hx-get="?fragment=comments" hx-target="#comments" hx-swap="outerHTML" {
+///             "Refresh"
+///         }
+///     }
+/// }
+/// ```
+pub async fn fragment(name: &str, content: Markup) -> Markup {
+    let (start, end) = markers(name);
+
+    Markup {
+        content: PreEscaped(format!("{start}{}{end}", content.content.0)),
+        linked_files: content.linked_files,
+    }
+}
+
+/// The content of the fragment named `name` in a page
+pub fn extract<'a>(html: &'a str, name: &str) -> Option<&'a str> {
+    let (start, end) = markers(name);
+
+    let (_, after_start) = html.split_once(&start)?;
+    let (content, _) = after_start.split_once(&end)?;
+
+    Some(content)
+}
+
+/// The comments around a fragment
+fn markers(name: &str) -> (String, String) {
+    // `--` can't be in a comment
+    let name = name.replace("--", "-");
+
+    (
+        format!("<!--wini-fragment:{name}-->"),
+        format!("<!--/wini-fragment:{name}-->"),
+    )
+}
@@ -0,0 +1,96 @@
+//! The conversion of the directories of the pages into routes.
+//!
+//! Used by `./build.rs` to generate the router of the pages (see [`crate::router`]), so it must
+//! only depend on `std`.
+
+/// A segment of the route of a page, from the name of its directory
+#[derive(Debug, PartialEq, Eq)]
+pub enum Segment<'a> {
+    /// `index`: the page of the parent directory
+    Index,
+    /// `about` => `/about`
+    Static(&'a str),
+    /// `[slug]` => `/{slug}`
+    Param(&'a str),
+    /// `[...path]` => `/{*path}`
+    CatchAll(&'a str),
+}
+
+impl<'a> Segment<'a> {
+    pub fn from_dir_name(dir_name: &'a str) -> Self {
+        match dir_name
+            .strip_prefix('[')
+            .and_then(|name| name.strip_suffix(']'))
+        {
+            Some(name) => {
+                match name.strip_prefix("...") {
+                    Some(name) => Self::CatchAll(name),
+                    None => Self::Param(name),
+                }
+            },
+            None if dir_name == "index" => Self::Index,
+            None => Self::Static(dir_name),
+        }
+    }
+
+    /// The segment in the route of `axum`, if there is one
+    pub fn route(&self) -> Option<String> {
+        match self {
+            Self::Index => None,
+            Self::Static(name) => Some((*name).to_owned()),
+            Self::Param(name) => Some(format!("{{{name}}}")),
+            Self::CatchAll(name) => Some(format!("{{*{name}}}")),
+        }
+    }
+
+    /// The name of the module of the directory.
+    ///
+    /// Directories that aren't valid identifiers must be declared with a `#[path]`:
+    /// `#[path = "[slug]/mod.rs"] pub mod slug;`
+    pub fn module(&self) -> String {
+        match self {
+            Self::Index => "index".to_owned(),
+            Self::Static(name) | Self::Param(name) | Self::CatchAll(name) => name.replace('-', "_"),
+        }
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self, Self::Param(_) | Self::CatchAll(_))
+    }
+}
+
+
+/// The route of the page in the directory `dir_names`, relative to the pages directory.
+///
+/// `["blog", "[slug]"]` => `/blog/{slug}`
+pub fn route(dir_names: &[&str]) -> String {
+    let segments = dir_names
+        .iter()
+        .filter_map(|dir_name| Segment::from_dir_name(dir_name).route())
+        .collect::<Vec<_>>();
+
+    format!("/{}", segments.join("/"))
+}
+
+/// The path of the module of the page in the directory `dir_names`.
+///
+/// `("crate::pages", ["blog", "[slug]"])` => `crate::pages::blog::slug`
+pub fn module_path(pages_module: &str, dir_names: &[&str]) -> String {
+    dir_names
+        .iter()
+        .fold(pages_module.to_owned(), |module, dir_name| {
+            format!("{module}::{}", Segment::from_dir_name(dir_name).module())
+        })
+}
+
+/// The module containing the pages, from `path.pages` of `./wini.toml`.
+///
+/// `./pages` => `crate::pages`
+pub fn pages_module(pages_path: &str) -> String {
+    let relative_path = pages_path
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .replace('/', "::");
+
+    format!("crate::{relative_path}")
+}
@@ -0,0 +1,214 @@
+//! Structured data (JSON-LD) rendered in the `<head>` of a page.
+//!
+//! Structured data is attached to a [`Markup`] as a linked file, so it propagates from nested
+//! components up to the page exactly like CSS and JavaScript files do. All the blocks found in
+//! the linked files are then merged by `template::template` into a single
+//! `<script type="application/ld+json">`.
+
+use {
+    crate::shared::wini::err::{ServerErrorKind, ServerResult},
+    maud::Markup,
+    serde::Serialize,
+};
+
+/// Prefix used to differentiate structured data from real files in `linked_files`
+pub const JSON_LD_PREFIX: &str = "ld+json:";
+
+/// Attach structured data to the page.
+///
+/// Can be used with the same syntax as components, so that it's propagated to the page from
+/// anywhere in the component tree.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use PROJECT_NAME_TO_RESOLVE::shared::wini::json_ld::{json_ld, Article};
+///
+/// #[page]
+/// pub async fn blog_post() -> ServerResult<Markup> {
+///     let article = Article {
+///         headline: "Hello world".to_owned(),
+///         ..Default::default()
+///     };
+///
+///     Ok(html! {
+///         [json_ld(&article)?]
+///         h1 { "Hello world" }
+///     })
+/// }
+/// ```
+pub async fn json_ld<T: Serialize>(data: &T) -> ServerResult<Markup> {
+    let json = serde_json::to_string(data)
+        .map_err(|err| ServerErrorKind::DebugedError(format!("Invalid JSON-LD: {err}")))?;
+
+    let mut markup = Markup::default();
+    markup
+        .linked_files
+        .insert(format!("{JSON_LD_PREFIX}{}", escape_for_script(&json)));
+
+    Ok(markup)
+}
+
+/// Merge all the structured data blocks in a single JSON-LD document.
+pub fn merge_blocks(mut blocks: Vec<&str>) -> Option<String> {
+    if blocks.is_empty() {
+        return None;
+    }
+
+    // `linked_files` is a set: sort the blocks so the output is stable between renders
+    blocks.sort_unstable();
+
+    Some(format!(
+        r#"{{"@context":"https://schema.org","@graph":[{}]}}"#,
+        blocks.join(",")
+    ))
+}
+
+/// Escape the characters that could close the `<script>` tag, or open an HTML comment in it.
+///
+/// `<`, `>` and `&` can only appear in JSON strings, where their unicode escape is equivalent.
+fn escape_for_script(json: &str) -> String {
+    json.replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026")
+}
+
+
+/// <https://schema.org/Person>
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "@type", rename = "Person", rename_all = "camelCase")]
+pub struct Person {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// <https://schema.org/Article>
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "@type", rename = "Article", rename_all = "camelCase")]
+pub struct Article {
+    pub headline: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub image: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub author: Vec<Person>,
+    /// ISO 8601 date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_published: Option<String>,
+    /// ISO 8601 date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_modified: Option<String>,
+}
+
+/// <https://schema.org/Offer>
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "@type", rename = "Offer", rename_all = "camelCase")]
+pub struct Offer {
+    pub price: String,
+    /// ISO 4217 currency code
+    pub price_currency: String,
+    /// Ex: `https://schema.org/InStock`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// <https://schema.org/Product>
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "@type", rename = "Product", rename_all = "camelCase")]
+pub struct Product {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub image: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sku: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub offers: Vec<Offer>,
+}
+
+/// <https://schema.org/ListItem>
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "@type", rename = "ListItem", rename_all = "camelCase")]
+pub struct ListItem {
+    /// Starts at 1
+    pub position: u32,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item: Option<String>,
+}
+
+/// <https://schema.org/BreadcrumbList>
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "@type", rename = "BreadcrumbList", rename_all = "camelCase")]
+pub struct BreadcrumbList {
+    pub item_list_element: Vec<ListItem>,
+}
+
+impl BreadcrumbList {
+    /// Create a breadcrumb from `(name, url)` pairs, in order.
+    pub fn from_links<N: Into<String>, U: Into<String>>(
+        links: impl IntoIterator<Item = (N, U)>,
+    ) -> Self {
+        Self {
+            item_list_element: links
+                .into_iter()
+                .zip(1..)
+                .map(|((name, url), position)| {
+                    ListItem {
+                        position,
+                        name: name.into(),
+                        item: Some(url.into()),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// <https://schema.org/Answer>
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "@type", rename = "Answer", rename_all = "camelCase")]
+pub struct Answer {
+    pub text: String,
+}
+
+/// <https://schema.org/Question>
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "@type", rename = "Question", rename_all = "camelCase")]
+pub struct Question {
+    pub name: String,
+    pub accepted_answer: Answer,
+}
+
+/// <https://schema.org/FAQPage>
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "@type", rename = "FAQPage", rename_all = "camelCase")]
+pub struct FaqPage {
+    pub main_entity: Vec<Question>,
+}
+
+impl FaqPage {
+    /// Create a FAQ from `(question, answer)` pairs.
+    pub fn from_pairs<Q: Into<String>, A: Into<String>>(
+        pairs: impl IntoIterator<Item = (Q, A)>,
+    ) -> Self {
+        Self {
+            main_entity: pairs
+                .into_iter()
+                .map(|(question, answer)| {
+                    Question {
+                        name: question.into(),
+                        accepted_answer: Answer {
+                            text: answer.into(),
+                        },
+                    }
+                })
+                .collect(),
+        }
+    }
+}
@@ -2,7 +2,10 @@
 //! Used in `#[layout]`s
 
 use {
-    crate::shared::wini::response::{FromResponseBody, FromResponseParts},
+    crate::shared::{
+        app_state::AppState,
+        wini::response::{FromResponseBody, FromResponseParts},
+    },
     axum::{
         body::Body,
         extract::FromRequestParts,
@@ -22,7 +25,7 @@ impl<T> IsFromRequestParts for T {
     default const IS_FROM_REQUEST_PARTS: bool = false;
 }
 
-impl<T: FromRequestParts<()>> IsFromRequestParts for T {
+impl<T: FromRequestParts<AppState>> IsFromRequestParts for T {
     const IS_FROM_REQUEST_PARTS: bool = true;
 }
 
@@ -36,7 +39,7 @@ impl<T> IsFromResponseBody for T {
     default const IS_FROM_RESPONSE_BODY: bool = false;
 }
 
-impl<T: FromResponseBody<()>> IsFromResponseBody for T {
+impl<T: FromResponseBody<AppState>> IsFromResponseBody for T {
     const IS_FROM_RESPONSE_BODY: bool = true;
 }
 
@@ -50,7 +53,7 @@ impl<T> IsFromResponseParts for T {
     default const IS_FROM_RESPONSE_PARTS: bool = false;
 }
 
-impl<T: FromResponseParts<()>> IsFromResponseParts for T {
+impl<T: FromResponseParts<AppState>> IsFromResponseParts for T {
     const IS_FROM_RESPONSE_PARTS: bool = true;
 }
 
@@ -14,14 +14,21 @@ pub static PUBLIC_ENDPOINTS: LazyLock<Vec<String>> =
     LazyLock::new(|| file::get_files_in_directory("./public").unwrap_or_default());
 
 /// An HashMap of all the CSS files, with their content being the value
+///
+/// The scoped style sheets have their selectors rewritten by [`scoped_css::scope_css`].
 pub static CSS_FILES: LazyLock<HashMap<FileName, FileContent>> = LazyLock::new(|| {
     get_files_in_directory_per_extensions("src", &["css"])
         .into_iter()
         .map(|file| {
-            (
-                format!("/{file}"),
-                std::fs::read_to_string(file).exit_with_msg_if_err("File should always exist."),
-            )
+            let content =
+                std::fs::read_to_string(&file).exit_with_msg_if_err("File should always exist.");
+            let content = if scoped_css::is_scoped(&file) {
+                scoped_css::scope_css(&content, &scoped_css::scope_attribute(&file))
+            } else {
+                content
+            };
+
+            (format!("/{file}"), content)
         })
         .collect()
 });
@@ -60,15 +67,32 @@ pub static PORT: LazyLock<u16> = LazyLock::new(|| {
 });
 
 
+pub mod action;
+pub mod base_path;
 pub mod cache;
 pub mod config;
+pub mod context;
+pub mod cookie;
+pub mod csp;
+pub mod csrf;
 pub mod dependencies;
 pub mod env;
 pub mod err;
+pub mod error_page;
+pub mod fragment;
+pub mod fs_router;
+pub mod json_ld;
 pub mod layer;
 pub mod layout;
 pub mod packages_files;
+pub mod problem_details;
+pub mod resource_hints;
 pub mod response;
+pub mod route;
+pub mod scoped_css;
+pub mod security_headers;
+pub mod session;
+pub mod startup;
 // IFFEAT ssg
 pub mod ssg;
 // ENDIF
@@ -0,0 +1,86 @@
+//! Error responses as `application/problem+json` ([RFC 7807](https://www.rfc-editor.org/rfc/rfc7807)),
+//! for the clients that prefer JSON to HTML, such as `fetch` or htmx.
+
+use {
+    axum::response::{IntoResponse, Response},
+    hyper::{StatusCode, header::CONTENT_TYPE},
+    serde::Serialize,
+};
+
+/// The content type of the problem details
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+
+/// The details of an error response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProblemDetails {
+    /// Always `about:blank`: the problem is described by the status code
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// The [`ErrorId`](crate::shared::wini::err::ErrorId) under which the error was logged
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_id: Option<String>,
+}
+
+impl ProblemDetails {
+    pub fn new(status_code: StatusCode) -> Self {
+        Self {
+            kind: "about:blank",
+            title: status_code.canonical_reason().unwrap_or("Error").to_owned(),
+            status: status_code.as_u16(),
+            detail: None,
+            error_id: None,
+        }
+    }
+}
+
+impl IntoResponse for ProblemDetails {
+    fn into_response(self) -> Response {
+        let status_code =
+            StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        match serde_json::to_string(&self) {
+            Ok(body) => (status_code, [(CONTENT_TYPE, PROBLEM_JSON)], body).into_response(),
+            Err(_) => status_code.into_response(),
+        }
+    }
+}
+
+
+/// Whether the `Accept` header of a request prefers JSON to HTML.
+///
+/// Clients accepting anything (`*/*`) get HTML, unless they explicitly list JSON, like `fetch`
+/// wrappers such as axios do.
+pub fn prefers_json(accept: &str) -> bool {
+    let mut json_quality = 0.0_f32;
+    let mut html_quality = 0.0_f32;
+
+    for media_range in accept.split(',') {
+        let mut params = media_range.split(';');
+        let media_type = params
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        let quality = params
+            .find_map(|param| param.trim().strip_prefix("q="))
+            .and_then(|quality| quality.parse::<f32>().ok())
+            .unwrap_or(1.0);
+
+        match media_type.as_str() {
+            "application/json" | "application/problem+json" => {
+                json_quality = json_quality.max(quality);
+            },
+            "text/html" | "application/xhtml+xml" | "text/*" => {
+                html_quality = html_quality.max(quality);
+            },
+            _ => {},
+        }
+    }
+
+    json_quality > html_quality
+}
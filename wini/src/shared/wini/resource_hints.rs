@@ -0,0 +1,128 @@
+//! Resource hints (`<link rel="preload">` and `<link rel="preconnect">`) rendered in the `<head>`
+//! of a page.
+//!
+//! Like structured data, hints are attached to a [`Markup`] as linked files, so they are
+//! deduplicated and propagated from nested components up to the page.
+
+use {
+    maud::Markup,
+    std::{collections::BTreeSet, fmt::Display},
+};
+
+/// Prefix used to differentiate files to preload from real files in `linked_files`
+pub const PRELOAD_PREFIX: &str = "preload:";
+/// Prefix used to differentiate origins to preconnect to from real files in `linked_files`
+pub const PRECONNECT_PREFIX: &str = "preconnect:";
+
+
+/// Preload a file needed early by the page, such as a font or an above-the-fold image.
+///
+/// Can be used with the same syntax as components. To preload static files, prefer the `preload`
+/// attribute of `#[page]`, `#[component]` and `#[layout]`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// html! {
+///     [preload(format!("/images/{}.webp", product.id))]
+///     img src=(format!("/images/{}.webp", product.id));
+/// }
+/// ```
+pub async fn preload(href: impl Display) -> Markup {
+    let mut markup = Markup::default();
+    markup
+        .linked_files
+        .insert(format!("{PRELOAD_PREFIX}{href}"));
+
+    markup
+}
+
+/// Open a connection to an origin the page will fetch resources from, such as a CDN.
+///
+/// The origins of the scripts and style sheets coming from another origin are already added
+/// automatically.
+pub async fn preconnect(origin: impl Display) -> Markup {
+    let mut markup = Markup::default();
+    markup
+        .linked_files
+        .insert(format!("{PRECONNECT_PREFIX}{origin}"));
+
+    markup
+}
+
+
+/// The resource hints of a page
+#[derive(Debug, Default)]
+pub struct ResourceHints {
+    /// Sorted so that the output is stable between renders
+    pub preloads: BTreeSet<String>,
+    pub preconnects: BTreeSet<String>,
+}
+
+impl ResourceHints {
+    /// Preconnect to the origin of `url` if it's an absolute URL
+    pub fn preconnect_to_origin_of(&mut self, url: &str) {
+        if let Some(origin) = origin(url) {
+            self.preconnects.insert(origin.to_owned());
+        }
+    }
+
+    /// The hints as the value of a `Link` header.
+    ///
+    /// Some proxies and CDNs use this header to send a `103 Early Hints` response while the page
+    /// is being rendered.
+    pub fn link_header(&self) -> Option<String> {
+        let preconnects = self
+            .preconnects
+            .iter()
+            .map(|origin| format!("<{origin}>; rel=preconnect"));
+        let preloads = self.preloads.iter().map(|href| {
+            let destination = preload_destination(href);
+            if needs_cors(destination) {
+                format!("<{href}>; rel=preload; as={destination}; crossorigin")
+            } else {
+                format!("<{href}>; rel=preload; as={destination}")
+            }
+        });
+
+        let links = preconnects.chain(preloads).collect::<Vec<_>>();
+
+        (!links.is_empty()).then(|| links.join(", "))
+    }
+}
+
+
+/// The `as` attribute of a preloaded file, based on its extension
+pub fn preload_destination(href: &str) -> &'static str {
+    let path = href.split(['?', '#']).next().unwrap_or_default();
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "woff2" | "woff" | "ttf" | "otf" => "font",
+        "avif" | "webp" | "png" | "jpg" | "jpeg" | "gif" | "svg" | "ico" => "image",
+        "css" => "style",
+        "js" | "mjs" => "script",
+        "vtt" => "track",
+        _ => "fetch",
+    }
+}
+
+/// Fonts and fetches are always requested in CORS mode, so their preload should also be
+pub fn needs_cors(destination: &str) -> bool {
+    matches!(destination, "font" | "fetch")
+}
+
+/// `https://cdn.example.com/lib.js` => `https://cdn.example.com`,
+/// `//cdn.example.com/lib.js` => `//cdn.example.com`
+fn origin(url: &str) -> Option<&str> {
+    let rest = match url.split_once("://") {
+        Some((scheme, rest)) if !scheme.contains(['/', '?', '#']) => rest,
+        _ => url.strip_prefix("//")?,
+    };
+    let authority_len = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+
+    url.get(..url.len() - rest.len() + authority_len)
+}
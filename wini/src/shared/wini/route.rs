@@ -0,0 +1,80 @@
+//! The URLs of the routes, used by the typed routes generated by `#[page(path = "...")]`.
+
+use {
+    super::base_path::with_base_path,
+    percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode},
+    serde::Serialize,
+    std::fmt::Display,
+};
+
+/// The characters encoded in a segment of a path: everything but the unreserved characters
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+
+/// The URL of a route, with its parameters replaced by their values and the base path of
+/// `./wini.toml` (see [`super::base_path`]).
+///
+/// The values are percent-encoded, except the `/` in the value of a `{*catch_all}` parameter. The
+/// query pairs are the ones returned by [`query_pair`], empty ones are skipped.
+///
+/// `url("/users/{id}", &[("id", &42)], &["tab=posts".to_owned()])` => `/users/42?tab=posts`
+pub fn url(route: &str, params: &[(&str, &dyn Display)], query: &[String]) -> String {
+    let mut url = String::with_capacity(route.len());
+    let mut rest = route;
+
+    while let Some((before, after)) = rest.split_once('{') &&
+        let Some((placeholder, after)) = after.split_once('}')
+    {
+        url.push_str(before);
+
+        let (name, is_catch_all) = match placeholder.strip_prefix('*') {
+            Some(name) => (name, true),
+            None => (placeholder, false),
+        };
+
+        match params.iter().find(|(param, _)| *param == name) {
+            Some((_, value)) => {
+                let value = value.to_string();
+                if is_catch_all {
+                    let segments = value
+                        .split('/')
+                        .map(|segment| utf8_percent_encode(segment, SEGMENT).to_string())
+                        .collect::<Vec<_>>();
+                    url.push_str(&segments.join("/"));
+                } else {
+                    url.extend(utf8_percent_encode(&value, SEGMENT));
+                }
+            },
+            // Kept as is, so that the missing parameter is visible
+            None => url.push_str(&format!("{{{placeholder}}}")),
+        }
+
+        rest = after;
+    }
+    url.push_str(rest);
+
+    let mut url = with_base_path(&url).into_owned();
+
+    let query = query
+        .iter()
+        .filter(|pair| !pair.is_empty())
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    if !query.is_empty() {
+        url.push('?');
+        url.push_str(&query.join("&"));
+    }
+
+    url
+}
+
+/// A pair of the query of a URL: `name=value`.
+///
+/// Empty if the value is `None`, or if it can't be represented in a query.
+pub fn query_pair<T: Serialize + ?Sized>(name: &str, value: &T) -> String {
+    serde_urlencoded::to_string([(name, value)]).unwrap_or_default()
+}
@@ -0,0 +1,291 @@
+//! Style sheets scoped to the components of their directory, named `scoped.css` (or
+//! `*.scoped.css`).
+//!
+//! The other style sheets are global: a `.button` of a component also matches the `.button`s of
+//! every page including it. When [`super::CSS_FILES`] are loaded, the selectors of a scoped style
+//! sheet are rewritten with the attribute of the style sheet, a hash of its path:
+//!
+//! ```css
+//! .button:hover::after { ... }
+//! /* becomes */
+//! .button:hover[data-wini-1a2b3c4d]::after { ... }
+//! ```
+//!
+//! and the `#[component]`s, `#[page]`s and `#[layout]`s of its directory add this attribute to
+//! every element written by their `html!`. The elements of the components they render don't have
+//! it, so the rules don't apply to them, but the children given to a component are written by its
+//! parent, and have the attribute of the parent.
+//!
+//! `:global(...)` isn't scoped: `:global(.dark) .button` only scopes `.button`, and `:global(body)`
+//! isn't scoped at all.
+//!
+//! Also used by `./macros`, to get the same attributes, so it must only depend on `std`.
+
+use std::borrow::Cow;
+
+/// The at-rules containing style rules, that are scoped too
+const GROUPING_AT_RULES: &[&str] = &[
+    "@container",
+    "@document",
+    "@layer",
+    "@media",
+    "@starting-style",
+    "@supports",
+];
+
+/// The pseudo-elements that can be written with a single `:`
+const LEGACY_PSEUDO_ELEMENTS: &[&str] = &[
+    ":after",
+    ":before",
+    ":first-letter",
+    ":first-line",
+];
+
+
+/// Whether a style sheet is scoped to the components of its directory
+pub fn is_scoped(style_sheet: &str) -> bool {
+    let file_name = style_sheet.rsplit('/').next().unwrap_or_default();
+
+    file_name == "scoped.css" || file_name.ends_with(".scoped.css")
+}
+
+/// The attribute of the elements matched by a scoped style sheet, from its path
+pub fn scope_attribute(style_sheet: &str) -> String {
+    // FNV-1a, to get the same hash in every build
+    let hash = style_sheet
+        .trim_start_matches('/')
+        .bytes()
+        .fold(0x811c_9dc5_u32, |hash, byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+        });
+
+    format!("data-wini-{hash:08x}")
+}
+
+/// Scope the selectors of a style sheet to the elements with `attribute`
+pub fn scope_css(css: &str, attribute: &str) -> String {
+    let scope = format!("[{attribute}]");
+    let mut scoped = String::with_capacity(css.len() + css.len() / 2);
+    scope_rules(css, &scope, &mut scoped);
+    scoped
+}
+
+
+fn scope_rules(css: &str, scope: &str, scoped: &mut String) {
+    // The start of the current rule, and of its block
+    let mut start = 0;
+    let mut block_start = 0;
+    let mut depth = 0_usize;
+
+    for (idx, char) in code_chars(css) {
+        match char {
+            '{' => {
+                if depth == 0 {
+                    block_start = idx;
+                }
+                depth += 1;
+            },
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    scope_rule(
+                        &css[start..block_start],
+                        &css[block_start + 1..idx],
+                        scope,
+                        scoped,
+                    );
+                    start = idx + 1;
+                }
+            },
+            // A statement, such as `@import`
+            ';' if depth == 0 => {
+                scoped.push_str(&css[start..=idx]);
+                start = idx + 1;
+            },
+            _ => {},
+        }
+    }
+
+    scoped.push_str(&css[start..]);
+}
+
+fn scope_rule(prelude: &str, block: &str, scope: &str, scoped: &mut String) {
+    let selectors = skip_comments_and_spaces(prelude);
+    scoped.push_str(&prelude[..prelude.len() - selectors.len()]);
+
+    if selectors.starts_with('@') {
+        let name = selectors
+            .split(|char: char| char.is_whitespace() || char == '(')
+            .next()
+            .unwrap_or_default();
+
+        scoped.push_str(selectors);
+        scoped.push('{');
+        if GROUPING_AT_RULES.contains(&name) {
+            scope_rules(block, scope, scoped);
+        } else {
+            // Such as `@font-face` or `@keyframes`
+            scoped.push_str(block);
+        }
+        scoped.push('}');
+        return;
+    }
+
+    let trimmed = selectors.trim_end();
+    let mut start = 0;
+    for end in top_level_indexes(trimmed, |char| char == ',').chain([trimmed.len()]) {
+        let selector = &trimmed[start..end];
+        let without_spaces = selector.trim_start();
+        scoped.push_str(&selector[..selector.len() - without_spaces.len()]);
+        scoped.push_str(&scope_selector(without_spaces.trim_end(), scope));
+        if end < trimmed.len() {
+            scoped.push(',');
+        }
+        start = end + 1;
+    }
+    scoped.push_str(&selectors[trimmed.len()..]);
+
+    scoped.push('{');
+    scoped.push_str(block);
+    scoped.push('}');
+}
+
+/// `.card > .title::before` => `.card > .title[data-wini-...]::before`
+fn scope_selector(selector: &str, scope: &str) -> String {
+    // The last compound selector is the one of the matched elements
+    let subject_start = top_level_indexes(selector, |char| {
+        char.is_whitespace() || matches!(char, '>' | '+' | '~')
+    })
+    .last()
+    .map_or(0, |idx| idx + 1);
+    let (ancestors, subject) = selector.split_at(subject_start);
+
+    let pseudo_element_start = top_level_indexes(subject, |char| char == ':')
+        .find(|&idx| {
+            let pseudo = &subject[idx..];
+            pseudo.starts_with("::") ||
+                LEGACY_PSEUDO_ELEMENTS.iter().any(|legacy| {
+                    pseudo.starts_with(legacy) &&
+                        !pseudo[legacy.len()..]
+                            .starts_with(|char: char| char.is_alphanumeric() || char == '-')
+                })
+        })
+        .unwrap_or(subject.len());
+    let (compound, pseudo_element) = subject.split_at(pseudo_element_start);
+
+    let is_global = compound.starts_with(":global(") &&
+        matching_paren(compound, ":global".len()) == Some(compound.len() - 1);
+    let scope = if is_global { "" } else { scope };
+
+    format!(
+        "{}{}{scope}{}",
+        unwrap_global(ancestors),
+        unwrap_global(compound),
+        unwrap_global(pseudo_element)
+    )
+}
+
+/// `:global(.dark) .button` => `.dark .button`
+fn unwrap_global(selector: &str) -> Cow<'_, str> {
+    let Some(start) = selector.find(":global(") else {
+        return Cow::Borrowed(selector);
+    };
+    let open = start + ":global".len();
+    let Some(close) = matching_paren(selector, open) else {
+        return Cow::Borrowed(selector);
+    };
+
+    Cow::Owned(format!(
+        "{}{}{}",
+        &selector[..start],
+        &selector[open + 1..close],
+        unwrap_global(&selector[close + 1..])
+    ))
+}
+
+/// The index of the `)` closing the `(` at `open`
+fn matching_paren(css: &str, open: usize) -> Option<usize> {
+    let mut depth = 0_usize;
+
+    code_chars(&css[open..]).find_map(|(idx, char)| {
+        match char {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return Some(open + idx);
+                }
+            },
+            _ => {},
+        }
+        None
+    })
+}
+
+/// The indexes of the characters outside of parentheses and brackets
+fn top_level_indexes<'a>(
+    css: &'a str,
+    is_wanted: impl Fn(char) -> bool + 'a,
+) -> impl Iterator<Item = usize> + 'a {
+    let mut depth = 0_usize;
+
+    code_chars(css).filter_map(move |(idx, char)| {
+        match char {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0 && is_wanted(char) => return Some(idx),
+            _ => {},
+        }
+        None
+    })
+}
+
+/// The characters of some CSS, with their index, outside of its comments, strings and escapes
+fn code_chars(css: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let mut chars = css.char_indices().peekable();
+
+    std::iter::from_fn(move || {
+        loop {
+            let (idx, char) = chars.next()?;
+            match char {
+                '/' if chars.peek().is_some_and(|(_, next)| *next == '*') => {
+                    chars.next();
+                    let mut previous = ' ';
+                    for (_, char) in chars.by_ref() {
+                        if previous == '*' && char == '/' {
+                            break;
+                        }
+                        previous = char;
+                    }
+                },
+                '"' | '\'' => {
+                    while let Some((_, next)) = chars.next() {
+                        if next == '\\' {
+                            chars.next();
+                        } else if next == char {
+                            break;
+                        }
+                    }
+                },
+                // Such as `.md\:flex`
+                '\\' => {
+                    chars.next();
+                },
+                _ => return Some((idx, char)),
+            }
+        }
+    })
+}
+
+fn skip_comments_and_spaces(mut css: &str) -> &str {
+    loop {
+        css = css.trim_start();
+        match css
+            .strip_prefix("/*")
+            .and_then(|comment| comment.find("*/"))
+        {
+            Some(end) => css = &css[end + "/**/".len()..],
+            None => return css,
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! The security headers sent with every response, configured in `[security.headers]` of
+//! `./wini.toml`.
+
+use {
+    crate::shared::wini::{
+        ENV_TYPE,
+        config::{ConfigHeaders, PerEnvironment, SERVER_CONFIG},
+        env::EnvType,
+        err::ExitWithMessageIfErr,
+    },
+    axum::http::{HeaderMap, HeaderName, HeaderValue},
+    std::{fmt::Display, str::FromStr, sync::LazyLock},
+};
+
+
+/// The security headers of the current environment
+pub static SECURITY_HEADERS: LazyLock<SecurityHeaders> = LazyLock::new(|| {
+    SecurityHeaders::from_config(SERVER_CONFIG.security().headers(), *ENV_TYPE)
+        .exit_with_msg_if_err("Invalid security headers")
+});
+
+type HeaderField = fn(&ConfigHeaders) -> &Option<String>;
+
+/// The headers that can be configured, their field in the configuration and their default value.
+///
+/// An empty default value means that the header isn't sent unless it's configured.
+pub const DEFAULT_HEADERS: [(&str, HeaderField, &str); 7] = [
+    (
+        "strict-transport-security",
+        ConfigHeaders::strict_transport_security,
+        "max-age=63072000; includeSubDomains",
+    ),
+    (
+        "x-content-type-options",
+        ConfigHeaders::x_content_type_options,
+        "nosniff",
+    ),
+    (
+        "referrer-policy",
+        ConfigHeaders::referrer_policy,
+        "strict-origin-when-cross-origin",
+    ),
+    (
+        "permissions-policy",
+        ConfigHeaders::permissions_policy,
+        "camera=(), microphone=(), geolocation=()",
+    ),
+    (
+        "x-frame-options",
+        ConfigHeaders::x_frame_options,
+        "SAMEORIGIN",
+    ),
+    (
+        "cross-origin-opener-policy",
+        ConfigHeaders::cross_origin_opener_policy,
+        "same-origin",
+    ),
+    // `require-corp` blocks all the cross-origin resources that don't opt-in with CORP
+    (
+        "cross-origin-embedder-policy",
+        ConfigHeaders::cross_origin_embedder_policy,
+        "",
+    ),
+];
+
+
+/// The security headers resolved for an environment
+#[derive(Debug, Default)]
+pub struct SecurityHeaders {
+    headers: Vec<(HeaderName, HeaderValue)>,
+    /// Path and the headers that shouldn't be sent under it
+    exemptions: Vec<(String, Vec<HeaderName>)>,
+}
+
+impl SecurityHeaders {
+    pub fn from_config(
+        config: &PerEnvironment<ConfigHeaders>,
+        env_type: EnvType,
+    ) -> Result<Self, SecurityHeadersError> {
+        let mut headers = vec![];
+
+        for (name, field, default) in DEFAULT_HEADERS {
+            let value = config
+                .get_with_env_type(env_type, |headers| field(headers).as_deref())
+                .unwrap_or(default);
+
+            if value.is_empty() {
+                continue;
+            }
+
+            let header_value = HeaderValue::from_str(value).map_err(|_| {
+                SecurityHeadersError::InvalidValue {
+                    header: name,
+                    value: value.to_owned(),
+                }
+            })?;
+
+            headers.push((HeaderName::from_static(name), header_value));
+        }
+
+        let mut exemptions = vec![];
+
+        if let Some(exempted) =
+            config.get_with_env_type(env_type, |headers| headers.exemptions().as_ref())
+        {
+            for (path, names) in exempted {
+                let names = names
+                    .iter()
+                    .map(|name| {
+                        HeaderName::from_str(name)
+                            .map_err(|_| SecurityHeadersError::InvalidName(name.to_owned()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                exemptions.push((path.trim_end_matches('/').to_owned(), names));
+            }
+        }
+
+        Ok(Self {
+            headers,
+            exemptions,
+        })
+    }
+
+    /// Add the security headers to the headers of a response to `path`.
+    ///
+    /// Exempted headers are also removed if they were already set, so that an exemption can be
+    /// used for headers set by other layers, such as `content-security-policy`.
+    pub fn apply(&self, path: &str, headers: &mut HeaderMap) {
+        for (name, value) in &self.headers {
+            if !headers.contains_key(name) {
+                headers.insert(name, value.clone());
+            }
+        }
+
+        for (exempted_path, names) in &self.exemptions {
+            if is_sub_path(path, exempted_path) {
+                for name in names {
+                    headers.remove(name);
+                }
+            }
+        }
+    }
+}
+
+/// `/widget` contains `/widget` and `/widget/embed`, but not `/widgets`
+fn is_sub_path(path: &str, parent: &str) -> bool {
+    path.strip_prefix(parent)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+
+#[derive(Debug)]
+pub enum SecurityHeadersError {
+    InvalidValue { header: &'static str, value: String },
+    InvalidName(String),
+}
+
+impl std::error::Error for SecurityHeadersError {}
+
+impl Display for SecurityHeadersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidValue { header, value } => {
+                write!(f, "Invalid value for the header `{header}`: {value:?}")
+            },
+            Self::InvalidName(name) => write!(f, "Invalid header name: {name:?}"),
+        }
+    }
+}
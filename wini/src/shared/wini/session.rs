@@ -0,0 +1,525 @@
+//! Sessions of the clients, configured in `[session]` of `./wini.toml`.
+//!
+//! The session is identified by a cookie holding its id, signed with the `SESSION_SECRET` of
+//! `./.env`. Its data is kept by a [`SessionStore`]:
+//! - `memory`: in the server, lost when it restarts,
+//! - `file`: in a JSON file per session, in the directory `path`,
+//! - or any other store, such as Redis or a database, set with [`set_store`] before the server
+//!   starts.
+//!
+//! `utils::wini::session::session_middleware` loads the session of each request, and saves it
+//! after the response if it changed. Pages, layouts and components get it with the [`Session`]
+//! extractor, and the flash messages with [`Flashes`].
+
+use {
+    super::{
+        config::{SERVER_CONFIG, SessionStoreKind},
+        err::{ServerError, ServerErrorKind, ServerResult},
+    },
+    axum::{extract::FromRequestParts, http::request::Parts},
+    base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD},
+    cached::{Cached, TimedSizedCache},
+    ring::hmac,
+    serde::{Deserialize, Serialize, de::DeserializeOwned},
+    std::{
+        collections::HashMap,
+        path::PathBuf,
+        pin::Pin,
+        sync::{Arc, LazyLock, Mutex, OnceLock, PoisonError},
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// The cookie identifying the session
+pub const SESSION_COOKIE: &str = "wini_session";
+
+/// The key of the flash messages in the data of a session
+const FLASHES_KEY: &str = "__wini_flashes";
+
+/// The data of a session
+pub type SessionData = HashMap<String, serde_json::Value>;
+
+pub type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = ServerResult<T>> + Send + 'a>>;
+
+/// The store chosen with [`set_store`], instead of the one of `./wini.toml`
+static STORE: OnceLock<Box<dyn SessionStore>> = OnceLock::new();
+
+/// The key signing the ids of the sessions, and of the CSRF sessions (see [`super::csrf`]).
+///
+/// Without `SESSION_SECRET`, which is only allowed in the local environment, a random key is used:
+/// the sessions don't survive a restart of the server.
+static KEY: LazyLock<hmac::Key> = LazyLock::new(|| {
+    match std::env::var("SESSION_SECRET") {
+        Ok(secret) => hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes()),
+        Err(_) => {
+            log::warn!("`SESSION_SECRET` isn't defined: the sessions will be lost on restart.");
+            let secret: [u8; 32] = rand::random();
+            hmac::Key::new(hmac::HMAC_SHA256, &secret)
+        },
+    }
+});
+
+
+/// Where the data of the sessions is kept
+pub trait SessionStore: Send + Sync + 'static {
+    /// The data of a session, if it exists and hasn't expired
+    fn load<'a>(&'a self, id: &'a str) -> StoreFuture<'a, Option<SessionData>>;
+
+    /// Create or replace the data of a session, for `max_age`
+    fn save<'a>(
+        &'a self,
+        id: &'a str,
+        data: &'a SessionData,
+        max_age: Duration,
+    ) -> StoreFuture<'a, ()>;
+
+    fn delete<'a>(&'a self, id: &'a str) -> StoreFuture<'a, ()>;
+
+    /// Delete the expired sessions, called regularly by `cron::launch_crons`.
+    ///
+    /// Nothing to do for the stores that expire the sessions by themselves, such as Redis.
+    fn purge_expired(&self) -> StoreFuture<'_, ()> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Use another store than the one of `./wini.toml`, such as Redis or a database.
+///
+/// Must be called before the server and the crons start, for example at the start of `main`.
+///
+/// # Panics
+///
+/// If a store is already used.
+pub fn set_store(store: impl SessionStore) {
+    assert!(
+        STORE.set(Box::new(store)).is_ok(),
+        "The store of the sessions is already set"
+    );
+}
+
+/// The store of the sessions
+pub fn store() -> &'static dyn SessionStore {
+    STORE
+        .get_or_init(|| {
+            let config = SERVER_CONFIG.session();
+
+            match config.store() {
+                SessionStoreKind::Memory => Box::new(MemoryStore::new()),
+                SessionStoreKind::File => Box::new(FileStore::new(config.path())),
+            }
+        })
+        .as_ref()
+}
+
+
+/// The sessions kept in the memory of the server
+pub struct MemoryStore {
+    sessions: Mutex<TimedSizedCache<String, (SystemTime, SessionData)>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(TimedSizedCache::with_size_and_lifespan(
+                100_000,
+                Duration::from_secs(*SERVER_CONFIG.session().max_age()),
+            )),
+        }
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionStore for MemoryStore {
+    fn load<'a>(&'a self, id: &'a str) -> StoreFuture<'a, Option<SessionData>> {
+        let mut sessions = self.sessions.lock().unwrap_or_else(PoisonError::into_inner);
+        let data = sessions
+            .cache_get(id)
+            .filter(|(expires, _)| *expires > SystemTime::now())
+            .map(|(_, data)| data.clone());
+
+        Box::pin(async move { Ok(data) })
+    }
+
+    fn save<'a>(
+        &'a self,
+        id: &'a str,
+        data: &'a SessionData,
+        max_age: Duration,
+    ) -> StoreFuture<'a, ()> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .cache_set(id.to_owned(), (SystemTime::now() + max_age, data.clone()));
+
+        Box::pin(async { Ok(()) })
+    }
+
+    fn delete<'a>(&'a self, id: &'a str) -> StoreFuture<'a, ()> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .cache_remove(id);
+
+        Box::pin(async { Ok(()) })
+    }
+
+    fn purge_expired(&self) -> StoreFuture<'_, ()> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .flush();
+
+        Box::pin(async { Ok(()) })
+    }
+}
+
+
+/// The sessions kept in a directory, with a JSON file per session
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SessionFile {
+    /// Seconds since the Unix epoch
+    expires: u64,
+    data: SessionData,
+}
+
+impl FileStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The ids are signed, and only contain URL-safe base64 characters
+    fn file(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+}
+
+impl SessionStore for FileStore {
+    fn load<'a>(&'a self, id: &'a str) -> StoreFuture<'a, Option<SessionData>> {
+        Box::pin(async move {
+            let Ok(content) = tokio::fs::read(self.file(id)).await else {
+                return Ok(None);
+            };
+
+            let file = serde_json::from_slice::<SessionFile>(&content)
+                .map_err(|err| ServerErrorKind::DebugedError(err.to_string()))?;
+
+            Ok((file.expires > unix_time(SystemTime::now())).then_some(file.data))
+        })
+    }
+
+    fn save<'a>(
+        &'a self,
+        id: &'a str,
+        data: &'a SessionData,
+        max_age: Duration,
+    ) -> StoreFuture<'a, ()> {
+        Box::pin(async move {
+            let file = SessionFile {
+                expires: unix_time(SystemTime::now() + max_age),
+                data: data.clone(),
+            };
+            let content = serde_json::to_vec(&file)
+                .map_err(|err| ServerErrorKind::DebugedError(err.to_string()))?;
+
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(self.file(id), content).await?;
+
+            Ok(())
+        })
+    }
+
+    fn delete<'a>(&'a self, id: &'a str) -> StoreFuture<'a, ()> {
+        Box::pin(async move {
+            // Already deleted if it doesn't exist
+            let _ = tokio::fs::remove_file(self.file(id)).await;
+            Ok(())
+        })
+    }
+
+    fn purge_expired(&self) -> StoreFuture<'_, ()> {
+        Box::pin(async move {
+            // No session was saved yet
+            let Ok(mut entries) = tokio::fs::read_dir(&self.dir).await else {
+                return Ok(());
+            };
+            let now = unix_time(SystemTime::now());
+
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                if path.extension().is_none_or(|extension| extension != "json") {
+                    continue;
+                }
+
+                // The files that can't be read are kept, they might be being written
+                let is_expired = tokio::fs::read(&path).await.ok().is_some_and(|content| {
+                    serde_json::from_slice::<SessionFile>(&content)
+                        .is_ok_and(|file| file.expires <= now)
+                });
+                if is_expired {
+                    let _ = tokio::fs::remove_file(path).await;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+
+/// The session of the current request.
+///
+/// The changes are saved after the response, and the cookie is only sent to the clients whose
+/// session has data.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[page]
+/// pub async fn render(session: Session) -> ServerResult<Markup> {
+///     let visits = session.get::<u32>("visits").unwrap_or_default() + 1;
+///     session.insert("visits", visits)?;
+///
+///     Ok(html! {
+///         p { "Visits: " (visits) }
+///     })
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Session(Arc<Mutex<SessionState>>);
+
+#[derive(Debug)]
+struct SessionState {
+    /// `None` for a new session
+    id: Option<String>,
+    data: SessionData,
+    changed: bool,
+    /// The id must be changed when it's saved
+    renew: bool,
+}
+
+impl Session {
+    /// A session loaded by the middleware
+    pub fn new(id: Option<String>, data: SessionData) -> Self {
+        Self(Arc::new(Mutex::new(SessionState {
+            id,
+            data,
+            changed: false,
+            renew: false,
+        })))
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, SessionState> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// A value of the session, if it exists and has the right type
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let state = self.state();
+        let value = state.data.get(key)?;
+
+        T::deserialize(value).ok()
+    }
+
+    pub fn insert<T: Serialize>(&self, key: &str, value: T) -> ServerResult<()> {
+        let value = serde_json::to_value(value)
+            .map_err(|err| ServerErrorKind::DebugedError(err.to_string()))?;
+
+        let mut state = self.state();
+        state.data.insert(key.to_owned(), value);
+        state.changed = true;
+
+        Ok(())
+    }
+
+    pub fn remove(&self, key: &str) {
+        let mut state = self.state();
+        if state.data.remove(key).is_some() {
+            state.changed = true;
+        }
+    }
+
+    /// Remove all the data of the session, such as when logging out
+    pub fn clear(&self) {
+        let mut state = self.state();
+        state.data.clear();
+        state.changed = true;
+    }
+
+    /// Change the id of the session while keeping its data, such as when logging in, so that an id
+    /// known before can't be used anymore
+    pub fn renew(&self) {
+        let mut state = self.state();
+        state.renew = true;
+        state.changed = true;
+    }
+
+    /// Add a message shown once, by the next page that reads the [`Flashes`]
+    pub fn flash(&self, message: impl Into<String>) -> ServerResult<()> {
+        let mut flashes = self.get::<Vec<String>>(FLASHES_KEY).unwrap_or_default();
+        flashes.push(message.into());
+
+        self.insert(FLASHES_KEY, flashes)
+    }
+
+    /// Read the flash messages, and remove them from the session
+    pub fn take_flashes(&self) -> Vec<String> {
+        let flashes = self.get::<Vec<String>>(FLASHES_KEY).unwrap_or_default();
+        self.remove(FLASHES_KEY);
+
+        flashes
+    }
+
+    /// Save the session in its store if it changed, and return the `Set-Cookie` to send if its
+    /// cookie changed
+    pub async fn save(&self, store: &dyn SessionStore) -> ServerResult<Option<String>> {
+        let (old_id, new_id, data) = {
+            let mut state = self.state();
+            if !state.changed {
+                return Ok(None);
+            }
+            state.changed = false;
+
+            let old_id = state.id.clone();
+            let new_id = if state.data.is_empty() {
+                None
+            } else if state.renew || old_id.is_none() {
+                Some(generate_id())
+            } else {
+                old_id.clone()
+            };
+
+            state.id.clone_from(&new_id);
+            state.renew = false;
+
+            (old_id, new_id, state.data.clone())
+        };
+
+        if let Some(old_id) = &old_id &&
+            new_id.as_ref() != Some(old_id)
+        {
+            store.delete(old_id).await?;
+        }
+
+        let max_age = *SERVER_CONFIG.session().max_age();
+        match new_id {
+            Some(id) => {
+                store.save(&id, &data, Duration::from_secs(max_age)).await?;
+
+                // The cookie is sent again to extend its `Max-Age`
+                Ok(Some(super::cookie::set(
+                    SESSION_COOKIE,
+                    &sign(&id),
+                    Some(max_age),
+                )))
+            },
+            // An empty session doesn't need a cookie anymore
+            None if old_id.is_some() => Ok(Some(super::cookie::set(SESSION_COOKIE, "", Some(0)))),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Default for Session {
+    /// A new session, without data
+    fn default() -> Self {
+        Self::new(None, SessionData::new())
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Session {
+    type Rejection = ServerError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<Session>().cloned().ok_or_else(|| {
+            ServerErrorKind::DebugedError(
+                "No session in the request. Is `[session]` enabled in `./wini.toml`?".to_owned(),
+            )
+            .into()
+        })
+    }
+}
+
+
+/// The flash messages of the session, removed from it once they are read.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[layout]
+/// pub async fn render(flashes: Flashes, child: Markup) -> Markup {
+///     html! {
+///         @for message in &flashes {
+///             p.flash { (message) }
+///         }
+///         (child)
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Flashes(pub Vec<String>);
+
+impl Flashes {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a Flashes {
+    type IntoIter = std::slice::Iter<'a, String>;
+    type Item = &'a String;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Flashes {
+    type Rejection = ServerError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let session = Session::from_request_parts(parts, state).await?;
+
+        Ok(Self(session.take_flashes()))
+    }
+}
+
+
+/// A new random id of session
+fn generate_id() -> String {
+    let bytes: [u8; 32] = rand::random();
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// The value of the cookie of a session: `<id>.<signature>`
+pub fn sign(id: &str) -> String {
+    format!("{id}.{}", signature(id))
+}
+
+/// The HMAC of a message with the `SESSION_SECRET`, in base64url
+pub fn signature(message: &str) -> String {
+    URL_SAFE_NO_PAD.encode(hmac::sign(&KEY, message.as_bytes()).as_ref())
+}
+
+/// The id of a session from the value of its cookie, if its signature is valid
+pub fn verify(cookie: &str) -> Option<&str> {
+    let (id, signature) = cookie.split_once('.')?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+
+    hmac::verify(&KEY, id.as_bytes(), &signature).ok()?;
+
+    Some(id)
+}
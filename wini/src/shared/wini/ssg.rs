@@ -4,18 +4,37 @@
 //! as static HTML files with associated assets, enabling deployment to static hosting services.
 
 use {
-    crate::shared::wini::{PORT, config::SERVER_CONFIG},
-    axum::{Router, routing::MethodRouter},
+    crate::shared::{
+        app_state::AppState,
+        wini::{PORT, base_path::with_base_path, config::SERVER_CONFIG, context::Context},
+    },
+    axum::{
+        Router,
+        extract::Request,
+        http::Uri,
+        middleware::{self, Next},
+        routing::MethodRouter,
+    },
+    regex::Regex,
     reqwest::Client,
     select::{document::Document, predicate::Name},
     std::{
         borrow::Cow,
         collections::{HashMap, HashSet},
         path::{Path, PathBuf},
-        sync::{Arc, LazyLock, Mutex},
+        sync::{
+            Arc,
+            LazyLock,
+            Mutex,
+            atomic::{AtomicBool, Ordering},
+        },
     },
 };
 
+/// Whether the routes are being rendered to files by [`render_routes_to_files`]. With
+/// `run-with-ssr`, they are only served to clients.
+pub(crate) static IS_GENERATING: AtomicBool = AtomicBool::new(false);
+
 /// A router builder for Static Site Generation that tracks routes and their parameter variants.
 ///
 /// `SsgRouter` allows you to register routes with optional parameter sets, then converts
@@ -43,7 +62,7 @@ use {
 /// // After that you can use it like
 /// let listener = tokio::net::TcpListener::bind("0.0.0.0:80").await.unwrap();
 ///
-/// axum::serve(listener, ssg_router.into_axum_router()).await.unwrap();
+/// axum::serve(listener, ssg_router.into_axum_router().with_state(state)).await.unwrap();
 /// ```
 ///
 /// All the routes that need to be SSG will be in `ROUTES_TO_AXUM`, in this case:
@@ -56,7 +75,8 @@ use {
 /// ```
 #[derive(Debug, Default)]
 pub(crate) struct SsgRouter<'l> {
-    routes: HashMap<&'l str, (MethodRouter<()>, Option<Vec<Vec<Cow<'l, str>>>>)>,
+    routes: HashMap<&'l str, (MethodRouter<AppState>, Option<Vec<Vec<Cow<'l, str>>>>)>,
+    context: Option<fn(&Uri) -> Context>,
 }
 
 impl<'l> SsgRouter<'l> {
@@ -85,7 +105,7 @@ impl<'l> SsgRouter<'l> {
     ///     .route("/about", get(about_handler));
     /// ```
     #[allow(unused, reason = "Not necessarily used")]
-    pub fn route(mut self, path: &'l str, m: MethodRouter<()>) -> Self {
+    pub fn route(mut self, path: &'l str, m: MethodRouter<AppState>) -> Self {
         self.routes.insert(path, (m, None));
         self
     }
@@ -137,13 +157,31 @@ impl<'l> SsgRouter<'l> {
     pub fn route_with_params(
         mut self,
         path: &'l str,
-        m: MethodRouter<()>,
+        m: MethodRouter<AppState>,
         params: Vec<Vec<Cow<'l, str>>>,
     ) -> Self {
         self.routes.insert(path, (m, Some(params)));
         self
     }
 
+    /// Sets the values of the [`Context`] of the routes when they are rendered statically, since
+    /// there isn't a real request to get them from.
+    ///
+    /// They are only used by [`render_routes_to_files`]: the requests of clients, with
+    /// `run-with-ssr`, keep their own values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let router = pages_ssg_router(&state)
+    ///     .context(|_uri| Context::new().with(CurrentUser::anonymous()).with(Locale::En));
+    /// ```
+    #[allow(unused, reason = "Not necessarily used")]
+    pub fn context(mut self, context: fn(&Uri) -> Context) -> Self {
+        self.context = Some(context);
+        self
+    }
+
     /// Converts the SSG router into an Axum router and registers all concrete routes for rendering.
     ///
     /// This method validates parameter counts against path segments and stores all
@@ -154,7 +192,7 @@ impl<'l> SsgRouter<'l> {
     /// * If a parameterized route is registered without parameters
     /// * If any parameter set has the wrong number of values for its route
     /// * If a static route is registered with parameters
-    pub fn into_axum_router(self) -> Router {
+    pub fn into_axum_router(self) -> Router<AppState> {
         let mut router = Router::new();
 
         for (path, (method_router, vec_params)) in self.routes {
@@ -191,10 +229,36 @@ impl<'l> SsgRouter<'l> {
             }
         }
 
+        if let Some(context) = self.context {
+            router = router.layer(middleware::from_fn(move |req: Request, next: Next| {
+                let ssg_context = IS_GENERATING
+                    .load(Ordering::Relaxed)
+                    .then(|| context(req.uri()));
+
+                async move {
+                    let Some(ssg_context) = ssg_context else {
+                        return next.run(req).await;
+                    };
+
+                    match Context::current() {
+                        Some(current) => {
+                            current.extend(&ssg_context);
+                            next.run(req).await
+                        },
+                        None => ssg_context.scope(next.run(req)).await,
+                    }
+                }
+            }));
+        }
+
         router
     }
 }
 
+/// The nonces of the Content-Security-Policy, that can't be kept in a page served to every client
+static NONCE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#" nonce="[^"]*""#).expect("valid regex"));
+
 static ROUTES_TO_AXUM: LazyLock<Arc<Mutex<HashSet<String>>>> =
     LazyLock::new(|| Arc::new(Mutex::new(HashSet::new())));
 
@@ -206,9 +270,13 @@ static ROUTES_TO_AXUM: LazyLock<Arc<Mutex<HashSet<String>>>> =
 /// 1. Fetches HTML content for each route from the running server
 /// 2. Parses HTML to find local assets (CSS, JS)
 /// 3. Downloads and saves assets preserving their directory structure
-/// 4. Writes each route's HTML to `dist/{route}/index.html`
+/// 4. Writes each route's HTML to `dist/{route}/index.html`, without the nonces of the
+///    Content-Security-Policy
 /// 5. Copies the entire public directory to `dist/`
 ///
+/// With a `base_path` in `./wini.toml`, everything is written in `dist/{base_path}/` instead, so
+/// that `dist/` can be served as is.
+///
 /// # File Structure
 ///
 /// Generated files follow this structure:
@@ -235,6 +303,7 @@ static ROUTES_TO_AXUM: LazyLock<Arc<Mutex<HashSet<String>>>> =
 /// * File system operations fail (permissions, disk space, etc.)
 /// * HTML parsing fails
 pub async fn render_routes_to_files() {
+    IS_GENERATING.store(true, Ordering::Relaxed);
     std::fs::create_dir_all("dist/").unwrap();
     let mut static_assets = HashSet::new();
 
@@ -243,6 +312,7 @@ pub async fn render_routes_to_files() {
     let reqwest_client = Client::new();
 
     for route in &routes {
+        let route = with_base_path(route);
         let resp_text = reqwest_client
             .get(format!("http://localhost:{}{route}", *PORT))
             .send()
@@ -282,7 +352,7 @@ pub async fn render_routes_to_files() {
 
         std::fs::create_dir_all(&path).unwrap();
         path.push("index.html");
-        std::fs::write(path, resp_text).expect("Couldn't write the file");
+        std::fs::write(path, strip_nonces(&resp_text).as_ref()).expect("Couldn't write the file");
     }
 
     for static_asset in static_assets {
@@ -301,7 +371,14 @@ pub async fn render_routes_to_files() {
         std::fs::write(path, resp_text).expect("Couldn't write the file");
     }
 
-    copy_dir_all(SERVER_CONFIG.path().public_from_src(), "dist").unwrap();
+    let mut public_dist = PathBuf::from("dist");
+    public_dist.extend(SERVER_CONFIG.base_path().split('/'));
+    copy_dir_all(SERVER_CONFIG.path().public_from_src(), public_dist).unwrap();
+}
+
+/// Remove the nonces of the Content-Security-Policy from a page generated statically
+pub fn strip_nonces(html: &str) -> Cow<'_, str> {
+    NONCE_REGEX.replace_all(html, "")
 }
 
 fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> std::io::Result<()> {
@@ -0,0 +1,279 @@
+//! Validation of the environment and of the configuration files, before the server starts.
+//!
+//! All the problems are collected and reported at once, so that a broken deployment doesn't have
+//! to be fixed one problem at a time.
+
+use {
+    super::{
+        base_path,
+        config::{Config, CsrfMode},
+        env::EnvType,
+        error_page,
+        packages_files::VecOrString,
+        security_headers::SecurityHeaders,
+        tsconfig::TsConfig,
+    },
+    crate::concat_paths,
+    serde::de::DeserializeOwned,
+    std::{
+        collections::{BTreeMap, HashMap},
+        fmt::{self, Display},
+        path::Path,
+        str::FromStr,
+    },
+    strum::IntoEnumIterator,
+};
+
+
+/// A problem preventing the server from starting
+#[derive(Debug)]
+pub struct StartupProblem {
+    /// The file causing the problem, with the position of the problem if it's known
+    pub location: String,
+    pub message: String,
+}
+
+/// All the problems found at startup
+#[derive(Debug, Default)]
+pub struct StartupReport {
+    pub problems: Vec<StartupProblem>,
+}
+
+impl StartupReport {
+    fn push(&mut self, location: impl Into<String>, message: impl Display) {
+        self.problems.push(StartupProblem {
+            location: location.into(),
+            message: message.to_string(),
+        });
+    }
+
+    /// Print the report and exit with a non-zero code if there are problems
+    pub fn exit_if_problems(&self) {
+        if !self.problems.is_empty() {
+            log::error!("{self}");
+            std::process::exit(1);
+        }
+    }
+}
+
+impl Display for StartupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.problems.len();
+        write!(
+            f,
+            "The server can't start: {count} problem{} found",
+            if count > 1 { "s" } else { "" }
+        )?;
+
+        for StartupProblem { location, message } in &self.problems {
+            write!(f, "\n\n  {location}")?;
+            for line in message.lines() {
+                write!(f, "\n    {line}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+/// Validate the environment and all the configuration files
+pub fn validate() -> StartupReport {
+    let mut report = StartupReport::default();
+
+    validate_env(&mut report);
+
+    let config = read_toml::<Config>("./wini.toml", &mut report);
+    if let Some(config) = &config {
+        validate_config(config, &mut report);
+    }
+
+    let packages = read_toml::<HashMap<String, VecOrString>>("./packages-files.toml", &mut report);
+    if let (Some(config), Some(packages)) = (&config, packages) &&
+        !packages.is_empty()
+    {
+        let modules = concat_paths!(config.path().public_from_src(), config.path().modules());
+        if !modules.is_dir() {
+            report.push(
+                "./wini.toml",
+                format!(
+                    "`path.modules` doesn't exist: {}\nIt's needed by the packages of `./packages-files.toml`.",
+                    modules.display()
+                ),
+            );
+        }
+    }
+
+    validate_tsconfig(&mut report);
+
+    // Registered by `#[error_page]` before `main`
+    for (file, message) in error_page::problems() {
+        report.push(format!("./{file}"), message);
+    }
+
+    report
+}
+
+fn validate_env(report: &mut StartupReport) {
+    if let Err(err) = dotenvy::dotenv() {
+        report.push(".env", format!("Couldn't load the environment: {err}"));
+    }
+
+    match std::env::var("ENV_TYPE") {
+        Ok(env_type) => {
+            if EnvType::from_str(&env_type).is_err() {
+                let expected = EnvType::iter()
+                    .map(|env_type| format!("{env_type:?}").to_uppercase())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                report.push(
+                    ".env",
+                    format!("Invalid `ENV_TYPE`: {env_type:?}\nExpected one of: {expected}"),
+                );
+            }
+        },
+        Err(_) => report.push(".env", "`ENV_TYPE` isn't defined"),
+    }
+
+    match std::env::var("PORT") {
+        Ok(port) => {
+            if port.parse::<u16>().is_err() {
+                report.push(".env", format!("`PORT` isn't a valid port: {port:?}"));
+            }
+        },
+        Err(_) => report.push(".env", "`PORT` isn't defined"),
+    }
+}
+
+fn validate_config(config: &Config, report: &mut StartupReport) {
+    let invalid_chars = base_path::invalid_chars(config.base_path());
+    if !invalid_chars.is_empty() {
+        report.push(
+            "./wini.toml",
+            format!(
+                "`base_path` can't contain {invalid_chars:?}: {:?}",
+                config.base_path()
+            ),
+        );
+    }
+
+    let csrf = config.security().csrf();
+    if *config.session().enabled() || (*csrf.enabled() && *csrf.mode() == CsrfMode::DoubleSubmit) {
+        match std::env::var("SESSION_SECRET") {
+            Ok(secret) if secret.len() < 32 => {
+                report.push(
+                    ".env",
+                    "`SESSION_SECRET` must be at least 32 characters long",
+                );
+            },
+            Ok(_) => {},
+            Err(_) => {
+                // A random secret is used in the local environment
+                if std::env::var("ENV_TYPE")
+                    .is_ok_and(|env_type| EnvType::from_str(&env_type) != Ok(EnvType::Local))
+                {
+                    report.push(
+                        ".env",
+                        "`SESSION_SECRET` isn't defined, but it's needed by `[session]` or \
+                         `[security.csrf]`",
+                    );
+                }
+            },
+        }
+    }
+
+    let public = config.path().public_from_src();
+    if !Path::new(&public).is_dir() {
+        report.push(
+            "./wini.toml",
+            format!("`path.public` doesn't exist: {public}"),
+        );
+    }
+
+    for (env_type, cache_for) in config.cache().missing_rules() {
+        report.push(
+            "./wini.toml",
+            format!(
+                "The cache rule for `{cache_for:?}` isn't defined in the environment `{env_type:?}`"
+            ),
+        );
+    }
+
+    // The same error is usually in all the environments, because it comes from `default`
+    let mut errors = BTreeMap::<String, Vec<EnvType>>::new();
+    for env_type in EnvType::iter() {
+        if let Err(err) = SecurityHeaders::from_config(config.security().headers(), env_type) {
+            errors.entry(err.to_string()).or_default().push(env_type);
+        }
+    }
+
+    for (err, env_types) in errors {
+        report.push(
+            "./wini.toml",
+            format!("[security.headers] {err} (in {env_types:?})"),
+        );
+    }
+}
+
+fn validate_tsconfig(report: &mut StartupReport) {
+    // `./tsconfig.json` is optional
+    let Ok(content) = std::fs::read_to_string("./tsconfig.json") else {
+        return;
+    };
+
+    if let Err(err) = serde_json::from_str::<TsConfig>(&content) {
+        // The position is already in the location
+        let message = err.to_string();
+        let message = message
+            .rsplit_once(" at line ")
+            .map_or(message.as_str(), |(message, _)| message);
+
+        report.push(
+            format!("./tsconfig.json:{}:{}", err.line(), err.column()),
+            message,
+        );
+    }
+}
+
+/// Parse a TOML file, reporting the position of the error if it's invalid
+fn read_toml<T: DeserializeOwned>(path: &str, report: &mut StartupReport) -> Option<T> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => {
+            report.push(path, format!("Couldn't read the file: {err}"));
+            return None;
+        },
+    };
+
+    match toml::from_str(&content) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            let location = match err.span() {
+                Some(span) => {
+                    let (line, column) = line_and_column(&content, span.start);
+                    format!("{path}:{line}:{column}")
+                },
+                None => path.to_owned(),
+            };
+
+            report.push(location, err.message());
+            None
+        },
+    }
+}
+
+/// The line and the column (starting at 1) of a byte offset in a text
+pub fn line_and_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = content.get(..offset).unwrap_or(content);
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .count() +
+        1;
+
+    (line, column)
+}
@@ -0,0 +1,74 @@
+//! Inlining of the critical style sheets, configured in `[critical_css]` of `./wini.toml`.
+//!
+//! When enabled, the style sheets of a page are either:
+//! - inlined in a `<style>`, if they are named `critical.css` (or `*.critical.css`), or if they are
+//!   smaller than `max_size`,
+//! - loaded asynchronously, without blocking the first paint.
+//!
+//! Only the style sheets from `./src` can be inlined: the ones coming from packages are always
+//! loaded asynchronously.
+
+use {crate::shared::wini::config::ConfigCriticalCss, std::collections::HashMap};
+
+
+/// Script switching the preloaded style sheets to real style sheets once they are loaded.
+///
+/// `load` doesn't bubble, so the event is captured at the level of the document. This makes it
+/// work even if a style sheet is loaded before the end of the parsing of the `<head>`.
+pub const ASYNC_STYLESHEETS_SCRIPT: &str = r#"document.addEventListener("load",e=>{if(e.target.dataset?.winiAsyncCss!==undefined)e.target.rel="stylesheet"},true)"#;
+
+
+/// The style sheets of a page, split depending on how they should be loaded
+#[derive(Debug, Default)]
+pub struct StyleSheets<'a> {
+    /// The content of the style sheets to inline
+    pub inlined: Vec<&'a str>,
+    /// Loaded without blocking the rendering
+    pub deferred: Vec<String>,
+    /// Loaded with a normal `<link rel="stylesheet">`
+    pub blocking: Vec<String>,
+}
+
+impl<'a> StyleSheets<'a> {
+    pub fn split(
+        mut style_sheets: Vec<String>,
+        config: &ConfigCriticalCss,
+        css_files: &'a HashMap<String, String>,
+    ) -> Self {
+        if !config.enabled() {
+            return Self {
+                blocking: style_sheets,
+                ..Default::default()
+            };
+        }
+
+        // `Files` is a set: sort the style sheets so the output is stable between renders
+        style_sheets.sort_unstable();
+
+        let mut split = Self::default();
+
+        for style_sheet in style_sheets {
+            match css_files.get(&style_sheet) {
+                Some(content)
+                    if is_critical(&style_sheet) || content.len() <= *config.max_size() =>
+                {
+                    split.inlined.push(content);
+                },
+                _ => split.deferred.push(style_sheet),
+            }
+        }
+
+        split
+    }
+}
+
+fn is_critical(style_sheet: &str) -> bool {
+    let file_name = style_sheet.rsplit('/').next().unwrap_or_default();
+
+    file_name == "critical.css" || file_name.ends_with(".critical.css")
+}
+
+/// Prevent the content of a style sheet from closing the `<style>` it's inlined in
+pub fn escape_for_style(css: &str) -> String {
+    css.replace("</", "<\\/")
+}
@@ -0,0 +1,132 @@
+//! The rendering of the pages that returned an error.
+//!
+//! In local and dev environments, an overlay with the error and its trace is rendered on top of
+//! the page. In the other environments, the error is only logged.
+
+use {
+    crate::shared::wini::{
+        csp::CspNonce,
+        env::EnvType,
+        err::{Backtrace, ErrorId, Trace, trace_on_one_line},
+    },
+    hyper::StatusCode,
+    maud::{Markup, PreEscaped, html},
+};
+
+/// The number of lines shown before the function of a trace
+const LINES_BEFORE: usize = 3;
+/// The maximum number of lines shown for the function of a trace
+const MAX_LINES: usize = 30;
+
+const STYLE: &str = "\
+#wini-error-overlay{position:fixed;inset:0;z-index:2147483647;overflow:auto;padding:2rem;\
+background:rgba(20,20,20,.95);color:#eee;font:14px/1.5 ui-monospace,monospace}\
+#wini-error-overlay h1{color:#ff6b6b;font-size:1.4rem;margin:0 0 1rem}\
+#wini-error-overlay pre{white-space:pre-wrap;margin:0}\
+#wini-error-overlay>pre{padding:1rem;background:#2a1515;border-left:4px solid #ff6b6b}\
+#wini-error-overlay details{margin-top:1rem;background:#1e1e1e}\
+#wini-error-overlay summary{padding:.5rem 1rem;cursor:pointer;color:#8cc8ff}\
+#wini-error-overlay .line{display:inline-block;width:4ch;color:#777;user-select:none}\
+#wini-error-overlay .highlight{background:#3a3a1a}";
+
+
+/// Should the overlay be rendered in an environment
+pub fn is_overlay_enabled(env_type: EnvType) -> bool {
+    matches!(env_type, EnvType::Local | EnvType::Dev)
+}
+
+/// Log an error on a single line, with its trace.
+///
+/// The errors with an [`ErrorId`] were already logged when their response was created, with the
+/// same trace: they aren't logged again.
+pub fn log_error(path: &str, status_code: StatusCode, backtrace: &Backtrace) {
+    if backtrace.error_id.is_some() {
+        return;
+    }
+
+    log::error!(
+        "status={} path={path:?} error={:?} trace={:?}",
+        status_code.as_u16(),
+        backtrace.err,
+        trace_on_one_line(&backtrace.trace),
+    );
+}
+
+/// The page sent when an error doesn't have an error page, in the environments without overlay
+pub fn generic_error_page(status_code: StatusCode, error_id: Option<&ErrorId>) -> Markup {
+    html! {
+        h1 { "Error " (status_code.as_u16()) }
+        p { "An error occurred while rendering this page." }
+        @if let Some(error_id) = error_id {
+            p { "Error ID: " code { (error_id) } }
+        }
+    }
+}
+
+/// Render the overlay of an error
+pub fn error_overlay(
+    status_code: StatusCode,
+    backtrace: &Backtrace,
+    nonce: Option<&CspNonce>,
+) -> Markup {
+    html! {
+        style nonce=[nonce] { (PreEscaped(STYLE)) }
+        div #wini-error-overlay role="alert" {
+            h1 { "Error " (status_code.as_u16()) " while rendering the page" }
+            @if let Some(error_id) = &backtrace.error_id {
+                p { "Error ID: " (error_id) }
+            }
+            pre { (format!("{:#?}", backtrace.err)) }
+            @if backtrace.trace.is_empty() {
+                p { "No trace: the error didn't go through any `#[page]` or `#[component]`." }
+            }
+            // The first trace is where the error happened
+            @for (idx, trace) in backtrace.trace.iter().enumerate() {
+                details open[idx == 0] {
+                    summary {
+                        (trace.function_name) " - " (trace.file_path)
+                    }
+                    (source_snippet(trace))
+                }
+            }
+        }
+    }
+}
+
+/// The source code of the function of a trace, read from the files of the project
+fn source_snippet(trace: &Trace) -> Markup {
+    let Ok(source) = std::fs::read_to_string(trace.file_path) else {
+        return html! { pre { "Source not available." } };
+    };
+
+    let lines = source.lines().collect::<Vec<_>>();
+    let fn_definition = format!("fn {}", trace.function_name);
+
+    let Some(fn_line) = lines.iter().position(|line| {
+        // Not a function whose name only starts with the same characters
+        line.split_once(&fn_definition)
+            .is_some_and(|(_, rest)| rest.starts_with(['(', '<']))
+    }) else {
+        return html! { pre { "Function not found in the file." } };
+    };
+
+    let start = fn_line.saturating_sub(LINES_BEFORE);
+    let end = lines
+        .iter()
+        .skip(fn_line)
+        .position(|line| line.starts_with('}'))
+        .map_or(lines.len(), |len| fn_line + len + 1)
+        .min(fn_line + MAX_LINES);
+
+    html! {
+        pre {
+            @for (idx, line) in lines.iter().enumerate().take(end).skip(start) {
+                span .highlight[idx == fn_line] {
+                    span .line { (idx + 1) }
+                    (line)
+                }
+                "\n"
+            }
+        }
+    }
+}
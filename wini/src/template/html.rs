@@ -1,28 +1,63 @@
-use maud::{DOCTYPE, Markup, PreEscaped};
+//! The HTML of the template around the pages, and around the fragments.
+
+use {
+    super::{
+        critical_css::{ASYNC_STYLESHEETS_SCRIPT, StyleSheets, escape_for_style},
+        navigation::{inline_key, is_rerun, key},
+    },
+    crate::shared::wini::{
+        base_path::with_base_path,
+        config::ConfigNavigation,
+        csp::CspNonce,
+        csrf::CsrfToken,
+        resource_hints::{ResourceHints, needs_cors, preload_destination},
+    },
+    maud::{DOCTYPE, Markup, PreEscaped},
+};
 
 pub fn html(
     s: &str,
     scripts_files: Vec<String>,
-    style_sheets: Vec<String>,
+    style_sheets: &StyleSheets,
     meta: &Markup,
+    structured_data: Option<&str>,
+    hints: &ResourceHints,
+    nonce: Option<&CspNonce>,
+    csrf_token: Option<&CsrfToken>,
+    navigation: &ConfigNavigation,
 ) -> String {
+    let key = |kind: &str, value: &str| key(navigation, kind, value);
+
     maud::html! {
         (DOCTYPE)
-        html lang="en" {
+        html lang="en" data-wini-navigation[*navigation.enabled()] data-wini-prefetch[*navigation.enabled() && *navigation.prefetch()] {
             head {
                 meta charset="UTF-8";
                 meta name="viewport" content="width=device-width, initial-scale=1.0";
                 (meta)
+                @if let Some(csrf_token) = csrf_token {
+                    meta name="csrf-token" content=(csrf_token);
+                }
 
-                @for style_sheet in style_sheets {
-                    link rel="stylesheet" href=(style_sheet);
+                @for origin in &hints.preconnects {
+                    link rel="preconnect" href=(origin) data-wini-key=[key("preconnect", origin)];
                 }
-                link rel="icon" href="/favicon.ico" sizes="any";
-                link rel="icon" href="/favicon.svg" type="image/svg+xml";
-                link rel="stylesheet" href="/main.css";
-                script src="/helpers.min.js" defer {}
+                @for href in &hints.preloads {
+                    @let destination = preload_destination(href);
+                    link rel="preload" href=(href) as=(destination) crossorigin[needs_cors(destination)] nonce=[nonce] data-wini-key=[key("preload", href)];
+                }
+
+                (style_sheets_tags(style_sheets, nonce, navigation))
+                link rel="icon" href=(with_base_path("/favicon.ico")) sizes="any" data-wini-key=[key("icon", "/favicon.ico")];
+                link rel="icon" href=(with_base_path("/favicon.svg")) type="image/svg+xml" data-wini-key=[key("icon", "/favicon.svg")];
+                link rel="stylesheet" href=(with_base_path("/main.css")) nonce=[nonce] data-wini-key=[key("stylesheet", "/main.css")];
+                script src=(with_base_path("/helpers.min.js")) nonce=[nonce] defer data-wini-key=[key("script", "/helpers.min.js")] {}
                 @for script in scripts_files {
-                    script src=(script) defer {}
+                    script src=(script) nonce=[nonce] defer data-wini-key=[key("script", &script)] data-wini-rerun[is_rerun(navigation, &script)] {}
+                }
+                @if let Some(structured_data) = structured_data {
+                    // Already escaped when added with `json_ld::json_ld`
+                    script type="application/ld+json" nonce=[nonce] { (PreEscaped(structured_data)) }
                 }
             }
             body {
@@ -32,3 +67,49 @@ pub fn html(
     }
     .into_string()
 }
+
+/// The style sheets of the page, linked, inlined or loaded asynchronously
+fn style_sheets_tags(
+    style_sheets: &StyleSheets,
+    nonce: Option<&CspNonce>,
+    navigation: &ConfigNavigation,
+) -> Markup {
+    let key = |kind: &str, value: &str| key(navigation, kind, value);
+
+    maud::html! {
+        @for style_sheet in &style_sheets.blocking {
+            link rel="stylesheet" href=(style_sheet) nonce=[nonce] data-wini-key=[key("stylesheet", style_sheet)];
+        }
+        @for content in &style_sheets.inlined {
+            style nonce=[nonce] data-wini-key=[inline_key(navigation, "style", content)] { (PreEscaped(escape_for_style(content))) }
+        }
+        @if !style_sheets.deferred.is_empty() {
+            script nonce=[nonce] data-wini-key=[inline_key(navigation, "script", ASYNC_STYLESHEETS_SCRIPT)] { (PreEscaped(ASYNC_STYLESHEETS_SCRIPT)) }
+            @for style_sheet in &style_sheets.deferred {
+                link rel="preload" href=(style_sheet) as="style" data-wini-async-css nonce=[nonce] data-wini-key=[key("stylesheet", style_sheet)];
+            }
+            noscript {
+                @for style_sheet in &style_sheets.deferred {
+                    link rel="stylesheet" href=(style_sheet);
+                }
+            }
+        }
+    }
+}
+
+/// A fragment of a page, without the template.
+///
+/// The style sheets are always linked instead of inlined, and the tags don't have a nonce: the
+/// fragment is added to a page that has its own nonce, which isn't the one of this response.
+pub fn fragment(s: &str, scripts_files: Vec<String>, style_sheets: Vec<String>) -> String {
+    maud::html! {
+        @for style_sheet in style_sheets {
+            link rel="stylesheet" href=(style_sheet);
+        }
+        @for script in scripts_files {
+            script src=(script) defer {}
+        }
+        (PreEscaped(s))
+    }
+    .into_string()
+}
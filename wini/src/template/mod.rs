@@ -2,65 +2,118 @@ use {
     crate::{
         concat_paths,
         shared::wini::{
-            PUBLIC_ENDPOINTS,
+            CSS_FILES,
+            ENV_TYPE,
+            base_path::{is_external, with_base_path},
             config::SERVER_CONFIG,
+            csp::CspNonce,
             dependencies::{SCRIPTS_DEPENDENCIES, normalize_relative_path},
-            err::{ServerErrorKind, ServerResult},
+            env::EnvType,
+            err::{Backtrace, ServerErrorKind, ServerResult},
+            fragment::{self, Fragment},
+            json_ld::{self, JSON_LD_PREFIX},
             layer::Files,
             packages_files::{PACKAGES_FILES, VecOrString},
+            resource_hints::{PRECONNECT_PREFIX, PRELOAD_PREFIX, ResourceHints},
         },
-        utils::wini::buffer::buffer_to_string,
+        utils::wini::{buffer::buffer_to_string, csrf::add_token_to_response},
     },
-    axum::{
-        body::Body,
-        extract::Request,
-        middleware::Next,
-        response::{IntoResponse, Response},
+    axum::{body::Body, extract::Request, http::HeaderValue, middleware::Next, response::Response},
+    critical_css::StyleSheets,
+    error::{error_overlay, generic_error_page, is_overlay_enabled, log_error},
+    hyper::{
+        StatusCode,
+        header::{CONTENT_LENGTH, CONTENT_TYPE, LINK, TRANSFER_ENCODING},
     },
-    hyper::header::{CONTENT_LENGTH, TRANSFER_ENCODING},
     meta::add_meta_tags,
     std::collections::HashSet,
-    tower_http::services::ServeFile,
 };
 
-mod html;
+pub mod critical_css;
+pub mod error;
+pub mod html;
 mod meta;
+pub mod navigation;
 
 
 
 /// Use the basic template of HTML
 pub async fn template(req: Request, next: Next) -> ServerResult<Response> {
-    let path = &req.uri().path().to_string();
+    template_for_env(*ENV_TYPE, req, next).await
+}
 
+/// [`template`] in another environment than the current one, which chooses how the errors are
+/// rendered
+pub async fn template_for_env(
+    env_type: EnvType,
+    req: Request,
+    next: Next,
+) -> ServerResult<Response> {
+    let path = &req.uri().path().to_string();
 
-    if (*PUBLIC_ENDPOINTS).contains(path) {
-        return Ok(ServeFile::new(format!("./public{path}"))
-            .try_call(req)
-            .await
-            .map_err(|_| ServerErrorKind::PublicRessourceNotFound(path.to_owned()))
-            .into_response());
-    }
+    let nonce = req.extensions().get::<CspNonce>().cloned();
+    let fragment = req.extensions().get::<Fragment>().cloned();
 
     // Compute the request
     let rep = next.run(req).await;
     let (mut res_parts, res_body) = rep.into_parts();
 
-    let resp_str = buffer_to_string(res_body).await?;
+    let mut resp_str = buffer_to_string(res_body).await?;
+
+    if let Some(backtrace) = res_parts.extensions.get::<Backtrace>() {
+        log_error(path, res_parts.status, backtrace);
+
+        let is_html = res_parts
+            .headers
+            .get(CONTENT_TYPE)
+            .is_some_and(|content_type| content_type.as_bytes().starts_with(b"text/html"));
+
+        if is_overlay_enabled(env_type) {
+            resp_str.push_str(
+                &error_overlay(res_parts.status, backtrace, nonce.as_ref()).into_string(),
+            );
+        } else if !is_html {
+            // No error page rendered this error: don't send its raw text
+            resp_str =
+                generic_error_page(res_parts.status, backtrace.error_id.as_ref()).into_string();
+        }
+
+        res_parts.headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/html; charset=utf-8"),
+        );
+    }
 
     // Extract the meta tags from the response headers
     let meta_tags = add_meta_tags(&mut res_parts);
 
+    let csrf_token = add_token_to_response(&mut res_parts, &mut resp_str)?;
+
 
 
-    let (scripts, styles) = match res_parts.extensions.get::<Files>() {
+    let mut hints = ResourceHints::default();
+
+    let (scripts, styles, structured_data) = match res_parts.extensions.get::<Files>() {
         Some(files) => {
             // Convert the string separated by ; into a vec
             let mut scripts = vec![];
             let mut styles = vec![];
+            let mut json_ld_blocks = vec![];
 
             for file in files {
-                if !file.is_empty() {
-                    let formatted_file = format!("/{file}");
+                if let Some(block) = file.strip_prefix(JSON_LD_PREFIX) {
+                    json_ld_blocks.push(block);
+                } else if let Some(href) = file.strip_prefix(PRELOAD_PREFIX) {
+                    hints.preloads.insert(href.to_owned());
+                } else if let Some(origin) = file.strip_prefix(PRECONNECT_PREFIX) {
+                    hints.preconnects.insert(origin.to_owned());
+                } else if !file.is_empty() {
+                    // The files of the site are relative to `/`
+                    let formatted_file = if is_external(file) {
+                        file.to_string()
+                    } else {
+                        format!("/{file}")
+                    };
                     if file.ends_with("css") {
                         styles.push(formatted_file);
                     } else if file.ends_with("js") {
@@ -73,13 +126,76 @@ pub async fn template(req: Request, next: Next) -> ServerResult<Response> {
 
             styles.extend(css_included_from_dependencies);
 
-            (scripts, styles)
+            for file in scripts.iter().chain(&styles) {
+                hints.preconnect_to_origin_of(file);
+            }
+
+            (scripts, styles, json_ld::merge_blocks(json_ld_blocks))
         },
-        None => (Vec::new(), Vec::new()),
+        None => (Vec::new(), Vec::new(), None),
     };
 
+    // The files are found from `/`, but served under the base path
+    let scripts = scripts
+        .iter()
+        .map(|script| with_base_path(script).into_owned())
+        .collect();
+    hints.preloads = hints
+        .preloads
+        .iter()
+        .map(|href| with_base_path(href).into_owned())
+        .collect();
+
     // Compute the HTML to send
-    let html = html::html(&resp_str, scripts, styles, &meta_tags);
+    let html = match &fragment {
+        Some(fragment) => {
+            let content = match fragment {
+                Fragment::Body => resp_str.as_str(),
+                Fragment::Named(name) => {
+                    fragment::extract(&resp_str, name)
+                        .ok_or(ServerErrorKind::Status(StatusCode::NOT_FOUND))?
+                },
+            };
+
+            let styles = styles
+                .iter()
+                .map(|style| with_base_path(style).into_owned())
+                .collect();
+
+            html::fragment(content, scripts, styles)
+        },
+        None => {
+            let mut style_sheets =
+                StyleSheets::split(styles, SERVER_CONFIG.critical_css(), &CSS_FILES);
+            for style_sheet in style_sheets
+                .blocking
+                .iter_mut()
+                .chain(&mut style_sheets.deferred)
+            {
+                *style_sheet = with_base_path(style_sheet).into_owned();
+            }
+
+            html::html(
+                &resp_str,
+                scripts,
+                &style_sheets,
+                &meta_tags,
+                structured_data.as_deref(),
+                &hints,
+                nonce.as_ref(),
+                csrf_token.as_ref(),
+                SERVER_CONFIG.navigation(),
+            )
+        },
+    };
+
+    if let Some(link) = hints.link_header() &&
+        *SERVER_CONFIG.resource_hints().link_header()
+    {
+        res_parts
+            .headers
+            .append(LINK, HeaderValue::from_str(&link)?);
+    }
 
     // Recalculate the length
     *res_parts.headers.entry(CONTENT_LENGTH).or_insert(0.into()) = html.len().into();
@@ -0,0 +1,45 @@
+//! The client-side navigation of `./public/helpers.js`, configured in `[navigation]` of
+//! `./wini.toml`.
+//!
+//! When enabled, the links to the pages of the site don't reload the page: the next page is
+//! fetched, its `<body>` replaces the current one, and only the new tags of its `<head>` are added.
+//! The tags of the template are identified between the pages by their `data-wini-key`, so that a
+//! style sheet or a script shared by both pages isn't loaded again.
+//!
+//! A script is loaded only once, except if it's named `rerun.js` (or `*.rerun.js`): it's then run
+//! again after each navigation to a page that uses it.
+//!
+//! Links with a `data-wini-reload` attribute are followed normally, and the pages keep working
+//! without JavaScript.
+//!
+//! With `prefetch`, the pages are fetched as soon as their links are hovered or focused, except the
+//! ones with `data-wini-reload`: it must be added to the links that change something, such as a
+//! `/logout`.
+
+use {
+    crate::shared::wini::config::ConfigNavigation,
+    std::hash::{DefaultHasher, Hash, Hasher},
+};
+
+
+/// The identifier of a tag of the template, if the navigation is enabled.
+///
+/// `key(config, "stylesheet", "/main.css")` => `stylesheet:/main.css`
+pub fn key(config: &ConfigNavigation, kind: &str, value: &str) -> Option<String> {
+    config.enabled().then(|| format!("{kind}:{value}"))
+}
+
+/// The identifier of an inlined `<style>` or `<script>`, from its content
+pub fn inline_key(config: &ConfigNavigation, kind: &str, content: &str) -> Option<String> {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+
+    key(config, kind, &format!("{:x}", hasher.finish()))
+}
+
+/// Whether a script must run again after each navigation
+pub fn is_rerun(config: &ConfigNavigation, script: &str) -> bool {
+    let file_name = script.rsplit('/').next().unwrap_or_default();
+
+    *config.enabled() && (file_name == "rerun.js" || file_name.ends_with(".rerun.js"))
+}
@@ -1,9 +1,16 @@
 use {
     crate::shared::wini::{cache::CacheCategory, config::SERVER_CONFIG, err::ServerResult},
-    axum::{http::HeaderValue, middleware::Next, response::Response},
-    hyper::header::CACHE_CONTROL,
+    axum::{
+        http::{HeaderMap, HeaderValue},
+        middleware::Next,
+        response::Response,
+    },
+    hyper::header::{CACHE_CONTROL, VARY},
 };
 
+/// The cache rule of the responses that are specific to a client, such as the ones setting a cookie
+pub const PRIVATE_CACHE_RULE: &str = "private, no-store";
+
 /// Add cache to an axum response
 pub fn add_cache(mut response: Response, cache_rule: &str) -> ServerResult<Response> {
     response
@@ -13,17 +20,46 @@ pub fn add_cache(mut response: Response, cache_rule: &str) -> ServerResult<Respo
     Ok(response)
 }
 
-/// Add the HTML cache rule
+/// Keep a response out of the shared caches, and out of the caches of the other cookies
+pub fn make_private(headers: &mut HeaderMap) {
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static(PRIVATE_CACHE_RULE));
+
+    let varies_on_cookie = headers.get_all(VARY).iter().any(|vary| {
+        vary.to_str().is_ok_and(|vary| {
+            vary.split(',')
+                .any(|header| header.trim() == "*" || header.trim().eq_ignore_ascii_case("cookie"))
+        })
+    });
+    if !varies_on_cookie {
+        headers.append(VARY, HeaderValue::from_static("Cookie"));
+    }
+}
+
+/// Whether a response was made private with [`make_private`]
+pub fn is_private(headers: &HeaderMap) -> bool {
+    headers
+        .get(CACHE_CONTROL)
+        .and_then(|cache_rule| cache_rule.to_str().ok())
+        .is_some_and(|cache_rule| {
+            cache_rule
+                .split(',')
+                .any(|directive| matches!(directive.trim(), "private" | "no-store"))
+        })
+}
+
+/// Add the HTML cache rule, unless the response is private
 pub async fn html_middleware(
     req: hyper::Request<axum::body::Body>,
     next: Next,
 ) -> ServerResult<Response> {
     let rep = next.run(req).await;
     let (mut res_parts, res_body) = rep.into_parts();
-    res_parts.headers.insert(
-        CACHE_CONTROL,
-        HeaderValue::from_str(SERVER_CONFIG.cache().get_or_panic(CacheCategory::Html))?,
-    );
+    if !is_private(&res_parts.headers) {
+        res_parts.headers.insert(
+            CACHE_CONTROL,
+            HeaderValue::from_str(SERVER_CONFIG.cache().get_or_panic(CacheCategory::Html))?,
+        );
+    }
     let res = Response::from_parts(res_parts, res_body);
     Ok(res)
 }
@@ -0,0 +1,16 @@
+use {
+    crate::shared::wini::context::Context,
+    axum::{extract::Request, middleware::Next, response::Response},
+};
+
+/// Run the request in its own [`Context`], starting with its `Uri`.
+///
+/// Should be outside of the layouts and of the middlewares that [`provide`] values, so that they
+/// all share the same context.
+///
+/// [`provide`]: crate::shared::wini::context::provide
+pub async fn context_middleware(req: Request, next: Next) -> Response {
+    let context = Context::for_uri(req.uri().clone());
+
+    context.scope(next.run(req)).await
+}
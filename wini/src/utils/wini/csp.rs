@@ -0,0 +1,51 @@
+use {
+    crate::{
+        shared::wini::{
+            config::SERVER_CONFIG,
+            csp::{CspNonce, policy_with_nonce},
+            err::ServerResult,
+        },
+        utils::wini::cache::make_private,
+    },
+    axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response},
+    hyper::header::{CONTENT_SECURITY_POLICY, CONTENT_SECURITY_POLICY_REPORT_ONLY, CONTENT_TYPE},
+};
+
+/// Generate the nonce of the request, and add the Content-Security-Policy of the current
+/// environment to the response.
+///
+/// The HTML responses are made private: a nonce mustn't be reused, so their cached version can't
+/// be sent to another request. Should be outside of `cache::html_middleware`.
+pub async fn csp_middleware(mut req: Request, next: Next) -> ServerResult<Response> {
+    let nonce = CspNonce::generate();
+    req.extensions_mut().insert(nonce.clone());
+
+    let mut rep = next.run(req).await;
+
+    let Some(csp) = SERVER_CONFIG.security().csp() else {
+        return Ok(rep);
+    };
+
+    if let Some(policy) = csp.get(|csp| csp.policy().as_deref()) {
+        let header = if csp.get(|csp| *csp.report_only()).unwrap_or(false) {
+            CONTENT_SECURITY_POLICY_REPORT_ONLY
+        } else {
+            CONTENT_SECURITY_POLICY
+        };
+
+        rep.headers_mut().insert(
+            header,
+            HeaderValue::from_str(&policy_with_nonce(policy, &nonce))?,
+        );
+
+        let is_html = rep
+            .headers()
+            .get(CONTENT_TYPE)
+            .is_some_and(|content_type| content_type.as_bytes().starts_with(b"text/html"));
+        if is_html {
+            make_private(rep.headers_mut());
+        }
+    }
+
+    Ok(rep)
+}
@@ -0,0 +1,147 @@
+use {
+    crate::{
+        shared::wini::{
+            config::{ConfigCsrf, CsrfMode, SERVER_CONFIG},
+            csrf::{
+                CSRF_HEADER,
+                CsrfSession,
+                CsrfToken,
+                add_token_to_forms,
+                is_exempt,
+                is_safe_method,
+                token_from_form,
+                token_from_multipart,
+            },
+            err::{ServerErrorKind, ServerResult},
+        },
+        utils::wini::cache::make_private,
+    },
+    axum::{
+        body::{Body, to_bytes},
+        extract::Request,
+        http::{HeaderValue, response::Parts},
+        middleware::Next,
+        response::{IntoResponse, Response},
+    },
+    hyper::{
+        StatusCode,
+        header::{CONTENT_TYPE, SET_COOKIE},
+    },
+};
+
+/// The maximum size of a form read to find its token, the same as the default limit of axum
+const MAX_FORM_SIZE: usize = 2 * 1024 * 1024;
+
+/// Give the token of its session to the request, and reject the requests with an unsafe method
+/// that don't send it back.
+///
+/// The session is given to the response, for the template, that adds the token to its forms with
+/// [`add_token_to_response`].
+///
+/// Should be inside `error_page_middleware`, so that the rejected requests get an error page.
+pub async fn csrf_middleware(req: Request, next: Next) -> ServerResult<Response> {
+    csrf(SERVER_CONFIG.security().csrf(), req, next).await
+}
+
+/// [`csrf_middleware`] with another config than the one of `./wini.toml`
+pub async fn csrf(config: &ConfigCsrf, req: Request, next: Next) -> ServerResult<Response> {
+    if !config.enabled() {
+        return Ok(next.run(req).await);
+    }
+
+    let session = CsrfSession::from_headers(req.headers(), *config.mode());
+    // Nothing is kept by the server, so every page can render the token
+    if *config.mode() == CsrfMode::DoubleSubmit {
+        session.token();
+    }
+
+    let mut rep = match check(config, &session, req).await {
+        Ok(mut req) => {
+            req.extensions_mut().insert(session.clone());
+            next.run(req).await
+        },
+        // The error page can have forms too
+        Err(err) => err.into_response(),
+    };
+    rep.extensions_mut().insert(session);
+
+    Ok(rep)
+}
+
+/// The request, if it sends the token of its session when it needs to
+async fn check(config: &ConfigCsrf, session: &CsrfSession, req: Request) -> ServerResult<Request> {
+    if is_safe_method(req.method()) || is_exempt(req.uri().path(), req.headers(), config) {
+        return Ok(req);
+    }
+
+    let (parts, body) = req.into_parts();
+
+    let from_header = parts
+        .headers
+        .get(CSRF_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(ToOwned::to_owned);
+    let content_type = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .unwrap_or_default();
+    let is_form = content_type.starts_with("application/x-www-form-urlencoded");
+    let is_multipart = content_type.starts_with("multipart/form-data");
+
+    // The body is read to find the token, and then given back to the handler
+    let (sent, body) = match from_header {
+        Some(sent) => (Some(sent), body),
+        None if is_form || is_multipart => {
+            let bytes = to_bytes(body, MAX_FORM_SIZE)
+                .await
+                .map_err(|_| ServerErrorKind::Status(StatusCode::PAYLOAD_TOO_LARGE))?;
+            let sent = if is_form {
+                token_from_form(&bytes)
+            } else {
+                token_from_multipart(content_type, &bytes)
+            };
+            (sent, Body::from(bytes))
+        },
+        None => (None, body),
+    };
+
+    let existing_token = session.existing_token();
+    if !sent.is_some_and(|sent| existing_token.is_some_and(|token| token.matches(&sent))) {
+        return Err(ServerErrorKind::Status(StatusCode::FORBIDDEN).into());
+    }
+
+    Ok(Request::from_parts(parts, body))
+}
+
+/// Add the token of the session given to a response by [`csrf`] to the forms of its HTML (see
+/// [`add_token_to_forms`]), and the cookie of the session if it was created while handling it.
+/// Returns the token, if the session has one.
+///
+/// Called by the template, so that the forms of the layouts and of the error pages get it too.
+pub fn add_token_to_response(
+    parts: &mut Parts,
+    html: &mut String,
+) -> ServerResult<Option<CsrfToken>> {
+    let Some(session) = parts.extensions.remove::<CsrfSession>() else {
+        return Ok(None);
+    };
+
+    if let Some(with_tokens) = add_token_to_forms(html, || session.token()) {
+        *html = with_tokens;
+    }
+
+    // Only if the session exists, or was created while handling the request
+    let token = session.existing_token().cloned();
+    if token.is_some() {
+        make_private(&mut parts.headers);
+    }
+
+    if let Some(set_cookie) = session.set_cookie() {
+        parts
+            .headers
+            .append(SET_COOKIE, HeaderValue::from_str(set_cookie)?);
+    }
+
+    Ok(token)
+}
@@ -0,0 +1,65 @@
+use {
+    crate::shared::wini::{err::Backtrace, error_page, layer::Files},
+    axum::{
+        body::Body,
+        extract::Request,
+        http::{HeaderMap, HeaderValue},
+        middleware::Next,
+        response::Response,
+    },
+    hyper::{
+        StatusCode,
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+    },
+    std::borrow::Cow,
+};
+
+/// Replace the body of the error responses with the error page registered for their status code.
+///
+/// Should be the first layer after the routes, so that the error pages are rendered through the
+/// layouts and the template.
+pub async fn error_page_middleware(req: Request, next: Next) -> Response {
+    let rep = next.run(req).await;
+    let status_code = rep.status();
+
+    if !status_code.is_client_error() && !status_code.is_server_error() {
+        return rep;
+    }
+
+    let (mut res_parts, res_body) = rep.into_parts();
+    let backtrace = res_parts.extensions.get::<Backtrace>().cloned();
+
+    // Responses with a body meant for the client (HTML, JSON, ...) are kept as is
+    if backtrace.is_none() && !has_default_body(&res_parts.headers) {
+        return Response::from_parts(res_parts, res_body);
+    }
+
+    let Some(page) = error_page::get(status_code) else {
+        return Response::from_parts(res_parts, res_body);
+    };
+
+    let markup = page(status_code, backtrace).await;
+
+    let files: &mut Files = res_parts.extensions.get_or_insert_default();
+    files.extend(markup.linked_files.into_iter().map(Cow::Owned));
+
+    res_parts.headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    res_parts.headers.remove(CONTENT_LENGTH);
+
+    Response::from_parts(res_parts, Body::from(markup.content.0))
+}
+
+/// The body of a `StatusCode` (empty), or of a `ServerError` (text)
+fn has_default_body(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .is_none_or(|content_type| content_type.as_bytes().starts_with(b"text/plain"))
+}
+
+/// The fallback of the router, for all the routes that don't exist
+pub async fn not_found() -> StatusCode {
+    StatusCode::NOT_FOUND
+}
@@ -0,0 +1,25 @@
+use {
+    crate::shared::wini::fragment::Fragment,
+    axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response},
+    hyper::header::VARY,
+};
+
+/// Detect the fragment requests, so that the layouts and the template only send the requested
+/// part of the page.
+///
+/// Should be before the template and the layouts.
+pub async fn fragment_middleware(mut req: Request, next: Next) -> Response {
+    if let Some(fragment) = Fragment::from_request(req.headers(), req.uri().query()) {
+        req.extensions_mut().insert(fragment);
+    }
+
+    let mut resp = next.run(req).await;
+
+    // The same URL can be a full page or a fragment
+    resp.headers_mut().append(
+        VARY,
+        HeaderValue::from_static("hx-request, hx-boosted, x-wini-fragment"),
+    );
+
+    resp
+}
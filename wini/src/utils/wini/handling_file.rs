@@ -1,30 +1,36 @@
 use {
     crate::shared::wini::{
-        cache::{AddCache, CacheCategory},
-        config::SERVER_CONFIG,
-        err::{ServerErrorKind, ServerResult},
         CSS_FILES,
         JS_FILES,
         PUBLIC_ENDPOINTS,
+        cache::{AddCache, CacheCategory},
+        config::SERVER_CONFIG,
+        err::{ServerErrorKind, ServerResult},
     },
     axum::{
+        Router,
         extract::Request,
-        http::HeaderValue,
+        http::{HeaderValue, Method},
         response::{IntoResponse, Response},
     },
-    hyper::{header::CONTENT_TYPE, StatusCode},
+    hyper::header::CONTENT_TYPE,
+    tower::ServiceExt,
     tower_http::services::ServeFile,
 };
 
-/// This request handler is in charge of serving files.
+/// The fallback of the router, in charge of serving files.
 /// In particular, these kind of files, in that order:
 /// - public files (exposed files)
 /// - css files
 /// - javascript files
-pub async fn handle_file(req: Request) -> ServerResult<Response<axum::body::Body>> {
+///
+/// The routes take precedence over the files. The requests that don't correspond to a file get the
+/// response of `not_found`, the router rendering the error page of the routes that don't exist.
+pub async fn handle_file(req: Request, not_found: Router) -> ServerResult<Response> {
     let path = &req.uri().path().to_string();
+    let is_get = matches!(*req.method(), Method::GET | Method::HEAD);
 
-    if PUBLIC_ENDPOINTS.contains(path) {
+    if is_get && PUBLIC_ENDPOINTS.contains(path) {
         return Ok(ServeFile::new(format!("./public{path}"))
             .try_call(req)
             .await
@@ -32,23 +38,22 @@ pub async fn handle_file(req: Request) -> ServerResult<Response<axum::body::Body
             .into_response());
     }
 
-    if path.ends_with(".css") {
-        return if let Some(file) = CSS_FILES.get(path) {
-            css_into_response(file)
-        } else {
-            Err(StatusCode::NOT_FOUND.into())
-        };
+    if is_get &&
+        path.ends_with(".css") &&
+        let Some(file) = CSS_FILES.get(path)
+    {
+        return css_into_response(file);
     }
 
-    if path.ends_with(".js") {
-        return if let Some(file) = JS_FILES.get(path) {
-            js_into_response(file)
-        } else {
-            Err(StatusCode::NOT_FOUND.into())
-        };
+    if is_get &&
+        path.ends_with(".js") &&
+        let Some(file) = JS_FILES.get(path)
+    {
+        return js_into_response(file);
     }
 
-    Err(StatusCode::NOT_FOUND.into())
+    let Ok(rep) = not_found.oneshot(req).await;
+    Ok(rep)
 }
 
 fn js_into_response(file_content: &str) -> ServerResult<Response<axum::body::Body>> {
@@ -1,6 +1,14 @@
 pub mod buffer;
 pub mod cache;
+pub mod context;
 pub mod cron;
+pub mod csp;
+pub mod csrf;
+pub mod error_page;
 pub mod file;
+pub mod fragment;
 pub mod handling_file;
 pub mod path;
+pub mod problem_details;
+pub mod security_headers;
+pub mod session;
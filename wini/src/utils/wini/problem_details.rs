@@ -0,0 +1,67 @@
+use {
+    crate::shared::wini::{
+        err::ErrorId,
+        problem_details::{ProblemDetails, prefers_json},
+    },
+    axum::{
+        extract::Request,
+        middleware::Next,
+        response::{IntoResponse, Response},
+    },
+    hyper::header::{ACCEPT, CONTENT_LENGTH, CONTENT_TYPE},
+};
+
+/// Send the error responses as `application/problem+json` to the clients preferring JSON.
+///
+/// Should be after the template, so that it replaces the rendered error page. It can also be added
+/// to the routers of an API, which aren't rendered in a page.
+pub async fn problem_details_middleware(req: Request, next: Next) -> Response {
+    let prefers_json = req
+        .headers()
+        .get(ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(prefers_json);
+
+    let resp = next.run(req).await;
+    let status_code = resp.status();
+
+    if !prefers_json || (!status_code.is_client_error() && !status_code.is_server_error()) {
+        return resp;
+    }
+
+    // A JSON body was already chosen by the route
+    if resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .is_some_and(|content_type| {
+            content_type.to_str().is_ok_and(|content_type| {
+                content_type.starts_with("application/") && content_type.contains("json")
+            })
+        })
+    {
+        return resp;
+    }
+
+    let (mut res_parts, _) = resp.into_parts();
+
+    let mut problem = res_parts
+        .extensions
+        .remove::<ProblemDetails>()
+        .unwrap_or_else(|| ProblemDetails::new(status_code));
+
+    if problem.error_id.is_none() {
+        problem.error_id = res_parts
+            .extensions
+            .get::<ErrorId>()
+            .map(ToString::to_string);
+    }
+
+    let problem_resp = problem.into_response();
+    let (problem_parts, problem_body) = problem_resp.into_parts();
+
+    // Keep the other headers of the response, such as the error ID or the security headers
+    res_parts.headers.extend(problem_parts.headers);
+    res_parts.headers.remove(CONTENT_LENGTH);
+
+    Response::from_parts(res_parts, problem_body)
+}
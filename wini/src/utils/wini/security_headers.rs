@@ -0,0 +1,14 @@
+use {
+    crate::shared::wini::security_headers::SECURITY_HEADERS,
+    axum::{extract::Request, middleware::Next, response::Response},
+};
+
+/// Add the security headers of the current environment to the response
+pub async fn security_headers_middleware(req: Request, next: Next) -> Response {
+    let path = req.uri().path().to_owned();
+
+    let mut rep = next.run(req).await;
+    SECURITY_HEADERS.apply(&path, rep.headers_mut());
+
+    rep
+}
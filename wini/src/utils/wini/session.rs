@@ -0,0 +1,65 @@
+use {
+    crate::{
+        shared::wini::{
+            config::{ConfigSession, SERVER_CONFIG},
+            cookie,
+            err::ServerResult,
+            session::{SESSION_COOKIE, Session, store, verify},
+        },
+        utils::wini::cache::make_private,
+    },
+    axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response},
+    hyper::header::SET_COOKIE,
+};
+
+/// Load the session of the request, and save it after the response if it changed.
+///
+/// The responses to the requests with a session, or creating one, can depend on it: they are
+/// private to the client.
+///
+/// Should be outside of the layouts, so that they can read the session and its flash messages.
+pub async fn session_middleware(req: Request, next: Next) -> ServerResult<Response> {
+    session(SERVER_CONFIG.session(), req, next).await
+}
+
+/// [`session_middleware`] with another config than the one of `./wini.toml`
+pub async fn session(
+    config: &ConfigSession,
+    mut req: Request,
+    next: Next,
+) -> ServerResult<Response> {
+    if !config.enabled() {
+        return Ok(next.run(req).await);
+    }
+
+    let store = store();
+
+    let id = cookie::get(req.headers(), SESSION_COOKIE).and_then(verify);
+    let has_session = id.is_some();
+    let session = match id {
+        Some(id) => {
+            match store.load(id).await? {
+                Some(data) => Session::new(Some(id.to_owned()), data),
+                // Expired: a new session is created if it's needed
+                None => Session::default(),
+            }
+        },
+        None => Session::default(),
+    };
+
+    req.extensions_mut().insert(session.clone());
+
+    let mut rep = next.run(req).await;
+
+    let set_cookie = session.save(store).await?;
+    if has_session || set_cookie.is_some() {
+        make_private(rep.headers_mut());
+    }
+
+    if let Some(set_cookie) = set_cookie {
+        rep.headers_mut()
+            .append(SET_COOKIE, HeaderValue::from_str(&set_cookie)?);
+    }
+
+    Ok(rep)
+}
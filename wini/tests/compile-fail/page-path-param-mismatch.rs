@@ -0,0 +1,10 @@
+use wini_macros::page;
+
+#[page(path = "/users/{id}")]
+pub async fn user(user_id: u32) -> maud::Markup {
+    maud::html! {
+        h1 { "User " (user_id) }
+    }
+}
+
+fn main() {}
@@ -1,3 +1,10 @@
+// The implementations of `HttpError` specialize the one of all the errors, like in `./src/lib.rs`
+#![allow(
+    incomplete_features,
+    reason = "Needed to implement `HttpError` on error types"
+)]
+#![feature(specialization)]
+
 use std::time::{Duration, Instant};
 
 pub fn time_function<F, R>(f: F) -> Duration
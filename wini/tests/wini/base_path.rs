@@ -0,0 +1,54 @@
+use PROJECT_NAME_TO_RESOLVE::shared::wini::base_path::{
+    invalid_chars,
+    is_external,
+    normalize,
+    prefix,
+};
+
+#[test]
+fn normalized_base_paths() {
+    assert_eq!(normalize(""), "");
+    assert_eq!(normalize("/"), "");
+    assert_eq!(normalize("/docs"), "/docs");
+    assert_eq!(normalize("/docs/"), "/docs");
+    assert_eq!(normalize("docs/v2/"), "/docs/v2");
+}
+
+#[test]
+fn invalid_base_paths() {
+    assert!(invalid_chars("/docs/v2").is_empty());
+    assert_eq!(invalid_chars("/docs/{version}"), ['{', '}']);
+    assert_eq!(invalid_chars("/my docs?"), [' ', '?']);
+}
+
+#[test]
+fn prefixed_urls() {
+    assert_eq!(prefix("", "/main.css"), "/main.css");
+    assert_eq!(prefix("/docs", "/main.css"), "/docs/main.css");
+    assert_eq!(prefix("/docs", "/"), "/docs");
+    assert_eq!(
+        prefix("/docs", "/src/pages/index/style.css"),
+        "/docs/src/pages/index/style.css"
+    );
+}
+
+#[test]
+fn external_urls_are_kept() {
+    assert_eq!(
+        prefix("/docs", "https://cdn.example.com/lib.js"),
+        "https://cdn.example.com/lib.js"
+    );
+    assert_eq!(
+        prefix("/docs", "//cdn.example.com/lib.js"),
+        "//cdn.example.com/lib.js"
+    );
+    assert_eq!(prefix("/docs", "relative.css"), "relative.css");
+}
+
+#[test]
+fn external_urls() {
+    assert!(is_external("https://cdn.example.com/lib.js"));
+    assert!(is_external("//cdn.example.com/lib.js"));
+    assert!(!is_external("/main.css"));
+    assert!(!is_external("/redirect?to=https://example.com"));
+}
@@ -0,0 +1,78 @@
+use {
+    PROJECT_NAME_TO_RESOLVE::shared::wini::context::{Context, ctx, provide},
+    hyper::Uri,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Locale(&'static str);
+
+#[derive(Debug, Clone, PartialEq)]
+struct CurrentUser(&'static str);
+
+#[test]
+fn values_per_type() {
+    let context = Context::new().with(Locale("en")).with(CurrentUser("wini"));
+    assert_eq!(context.get::<Locale>(), Some(Locale("en")));
+    assert_eq!(context.get::<CurrentUser>(), Some(CurrentUser("wini")));
+    assert_eq!(context.get::<Uri>(), None);
+
+    // Replaced
+    context.insert(Locale("fr"));
+    assert_eq!(context.get::<Locale>(), Some(Locale("fr")));
+
+    // Shared by the clones
+    context.clone().insert(CurrentUser("other"));
+    assert_eq!(context.get::<CurrentUser>(), Some(CurrentUser("other")));
+
+    // Extended
+    let other = Context::new().with(Locale("de")).with(42_u32);
+    context.extend(&other);
+    assert_eq!(context.get::<Locale>(), Some(Locale("de")));
+    assert_eq!(context.get::<u32>(), Some(42));
+    context.extend(&context.clone());
+    assert_eq!(context.get::<u32>(), Some(42));
+}
+
+#[tokio::test]
+async fn outside_of_a_request() {
+    assert_eq!(ctx::<Locale>(), None);
+    assert!(!provide(Locale("en")));
+    assert!(Context::current().is_none());
+}
+
+#[tokio::test]
+async fn across_await_points() {
+    let context = Context::for_uri(Uri::from_static("/blog?page=2"));
+
+    let (uri, locale) = context
+        .clone()
+        .scope(async {
+            assert!(provide(Locale("en")));
+            tokio::task::yield_now().await;
+
+            (ctx::<Uri>(), ctx::<Locale>())
+        })
+        .await;
+
+    assert_eq!(uri.unwrap().path(), "/blog");
+    assert_eq!(locale, Some(Locale("en")));
+
+    // Kept in the context after the scope
+    assert_eq!(context.get::<Locale>(), Some(Locale("en")));
+}
+
+#[tokio::test]
+async fn nested_scopes() {
+    let outer = Context::new().with(Locale("en"));
+
+    outer
+        .scope(async {
+            Context::new()
+                .with(Locale("fr"))
+                .scope(async { assert_eq!(ctx::<Locale>(), Some(Locale("fr"))) })
+                .await;
+
+            assert_eq!(ctx::<Locale>(), Some(Locale("en")));
+        })
+        .await;
+}
@@ -0,0 +1,60 @@
+use {
+    PROJECT_NAME_TO_RESOLVE::{
+        shared::wini::config::ConfigCriticalCss,
+        template::critical_css::{StyleSheets, escape_for_style},
+    },
+    std::collections::HashMap,
+};
+
+fn css_files() -> HashMap<String, String> {
+    HashMap::from([
+        ("/src/pages/a/style.css".to_owned(), "a{}".to_owned()),
+        ("/src/pages/b/style.css".to_owned(), "b{}".repeat(100)),
+        ("/src/pages/b/critical.css".to_owned(), "c{}".repeat(100)),
+    ])
+}
+
+fn style_sheets() -> Vec<String> {
+    vec![
+        "/src/pages/b/style.css".to_owned(),
+        "/modules/leaflet/dist/leaflet.css".to_owned(),
+        "/src/pages/a/style.css".to_owned(),
+        "/src/pages/b/critical.css".to_owned(),
+    ]
+}
+
+#[test]
+fn disabled() {
+    let config: ConfigCriticalCss = toml::from_str("enabled = false").unwrap();
+    let css_files = css_files();
+    let split = StyleSheets::split(style_sheets(), &config, &css_files);
+
+    assert!(split.inlined.is_empty());
+    assert!(split.deferred.is_empty());
+    assert_eq!(split.blocking, style_sheets());
+}
+
+#[test]
+fn inlined_if_small_or_critical() {
+    let config: ConfigCriticalCss = toml::from_str("enabled = true\nmax_size = 10").unwrap();
+    let css_files = css_files();
+    let split = StyleSheets::split(style_sheets(), &config, &css_files);
+
+    assert_eq!(split.inlined, ["a{}", "c{}".repeat(100).as_str()]);
+    assert_eq!(
+        split.deferred,
+        [
+            "/modules/leaflet/dist/leaflet.css",
+            "/src/pages/b/style.css"
+        ]
+    );
+    assert!(split.blocking.is_empty());
+}
+
+#[test]
+fn cannot_close_style_tag() {
+    assert_eq!(
+        escape_for_style(r#"a::after{content:"</style>"}"#),
+        r#"a::after{content:"<\/style>"}"#
+    );
+}
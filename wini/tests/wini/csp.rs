@@ -0,0 +1,31 @@
+use PROJECT_NAME_TO_RESOLVE::shared::wini::csp::{CspNonce, policy_with_nonce};
+
+#[test]
+fn nonce_added_to_script_and_style() {
+    let nonce = CspNonce::generate();
+    let policy = policy_with_nonce(
+        "default-src 'self'; script-src 'self'; style-src 'self'; object-src 'none'",
+        &nonce,
+    );
+
+    assert_eq!(
+        policy,
+        format!(
+            "default-src 'self'; script-src 'self' 'nonce-{nonce}'; style-src 'self' \
+             'nonce-{nonce}'; object-src 'none'"
+        )
+    );
+}
+
+#[test]
+fn missing_directives_are_not_added() {
+    let nonce = CspNonce::generate();
+    let policy = policy_with_nonce("default-src 'self';", &nonce);
+
+    assert_eq!(policy, "default-src 'self'");
+}
+
+#[test]
+fn different_nonces() {
+    assert_ne!(CspNonce::generate(), CspNonce::generate());
+}
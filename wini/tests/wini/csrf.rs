@@ -0,0 +1,133 @@
+use {
+    PROJECT_NAME_TO_RESOLVE::shared::wini::{
+        config::ConfigCsrf,
+        csrf::{
+            CsrfToken,
+            add_token_to_forms,
+            is_exempt,
+            is_safe_method,
+            token_from_form,
+            token_from_multipart,
+        },
+    },
+    axum::http::{HeaderMap, HeaderValue, Method, header::AUTHORIZATION},
+};
+
+#[test]
+fn tokens_are_random() {
+    let token = CsrfToken::generate();
+
+    assert_eq!(token.as_str().len(), 43);
+    assert_ne!(token, CsrfToken::generate());
+}
+
+#[test]
+fn matching_tokens() {
+    let token = CsrfToken::generate();
+
+    assert!(token.matches(token.as_str()));
+    assert!(!token.matches(CsrfToken::generate().as_str()));
+    assert!(!token.matches(""));
+    assert!(!token.matches(&format!("{token}a")));
+}
+
+#[test]
+fn signed_tokens() {
+    let session_id = CsrfToken::generate().to_string();
+    let token = CsrfToken::signed(&session_id);
+
+    assert_eq!(token.as_str().len(), 43);
+    assert_ne!(token.as_str(), session_id);
+    assert_eq!(token, CsrfToken::signed(&session_id));
+    assert_ne!(token, CsrfToken::signed(CsrfToken::generate().as_str()));
+}
+
+#[test]
+fn safe_methods() {
+    assert!(is_safe_method(&Method::GET));
+    assert!(is_safe_method(&Method::HEAD));
+    assert!(!is_safe_method(&Method::POST));
+    assert!(!is_safe_method(&Method::DELETE));
+}
+
+#[test]
+fn exempt_requests() {
+    let config: ConfigCsrf = toml::from_str(r#"exempt = ["/webhooks/"]"#).unwrap();
+    let mut bearer = HeaderMap::new();
+    bearer.insert(AUTHORIZATION, HeaderValue::from_static("bearer abc"));
+    let mut basic = HeaderMap::new();
+    basic.insert(AUTHORIZATION, HeaderValue::from_static("Basic abc"));
+
+    assert!(is_exempt("/webhooks", &HeaderMap::new(), &config));
+    assert!(is_exempt("/webhooks/stripe", &HeaderMap::new(), &config));
+    assert!(!is_exempt("/webhooks-old", &HeaderMap::new(), &config));
+    assert!(is_exempt("/posts", &bearer, &config));
+    assert!(!is_exempt("/posts", &basic, &config));
+}
+
+#[test]
+fn token_of_a_form() {
+    assert_eq!(
+        token_from_form(b"name=wini&_csrf=abc%2Bd").as_deref(),
+        Some("abc+d")
+    );
+    assert_eq!(token_from_form(b"name=wini"), None);
+}
+
+#[test]
+fn token_of_a_multipart_form() {
+    let content_type = "multipart/form-data; boundary=\"XyZ\"";
+    let body = b"--XyZ\r\n\
+        Content-Disposition: form-data; name=\"file\"; filename=\"_csrf\"\r\n\
+        Content-Type: text/plain\r\n\
+        \r\n\
+        name=\"_csrf\"\r\n\r\n--Xy\r\n\
+        --XyZ\r\n\
+        Content-Disposition: form-data; name=\"_csrf\"\r\n\
+        \r\n\
+        abc+d\r\n\
+        --XyZ--\r\n";
+
+    assert_eq!(
+        token_from_multipart(content_type, body).as_deref(),
+        Some("abc+d")
+    );
+    assert_eq!(
+        token_from_multipart("multipart/form-data; boundary=other", body),
+        None
+    );
+    assert_eq!(
+        token_from_multipart(
+            content_type,
+            b"--XyZ\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\nwini\r\n--XyZ--\r\n"
+        ),
+        None
+    );
+}
+
+#[test]
+fn token_added_to_forms() {
+    let token = CsrfToken::generate();
+    let input = format!(r#"<input type="hidden" name="_csrf" value="{token}">"#);
+
+    assert_eq!(
+        add_token_to_forms(
+            r#"<form method="post"><input name="a"></form><form method="POST" action="/b"></form>"#,
+            || token.clone()
+        ),
+        Some(format!(
+            r#"<form method="post">{input}<input name="a"></form><form method="POST" action="/b">{input}</form>"#
+        ))
+    );
+
+    // Forms that don't post, that post to another site, or that already have it
+    let html = r#"<form><input name="q"></form>
+        <form method="get"></form>
+        <form method="post" action="https://example.com/"></form>
+        <form method="post" action="//example.com/"></form>
+        <form method="post"><input type="hidden" name="_csrf" value="a"></form>"#;
+    assert_eq!(
+        add_token_to_forms(html, || panic!("no token is needed")),
+        None
+    );
+}
@@ -0,0 +1,173 @@
+use {
+    PROJECT_NAME_TO_RESOLVE::shared::wini::{
+        env::EnvType,
+        err::{ERROR_ID_HEADER, ErrorId, HttpError, ServerError, ServerErrorKind, ServerResult},
+    },
+    axum::response::IntoResponse,
+    http_body_util::BodyExt,
+    hyper::StatusCode,
+    std::fmt::{self, Display},
+};
+
+async fn body_to_string(resp: axum::response::Response) -> String {
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn internal_details_only_in_local_and_dev() {
+    let err = ServerErrorKind::DebugedError("database password: hunter2".to_owned());
+
+    for env_type in [
+        EnvType::Local,
+        EnvType::Dev,
+        EnvType::Staging,
+        EnvType::Prod,
+    ] {
+        let resp = err.to_response_with_trace(env_type, None);
+
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let error_id = resp.extensions().get::<ErrorId>().cloned().unwrap();
+        assert_eq!(
+            resp.headers().get(ERROR_ID_HEADER).unwrap(),
+            error_id.as_str()
+        );
+
+        let body = body_to_string(resp).await;
+
+        match env_type {
+            EnvType::Local | EnvType::Dev => assert!(body.contains("hunter2")),
+            EnvType::Prod | EnvType::Staging => {
+                assert!(!body.contains("hunter2"));
+                assert!(body.contains(error_id.as_str()));
+            },
+        }
+    }
+}
+
+#[test]
+fn no_error_id_for_status_codes() {
+    let resp = ServerErrorKind::Status(StatusCode::NOT_FOUND).into_response();
+
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert!(resp.extensions().get::<ErrorId>().is_none());
+}
+
+#[tokio::test]
+async fn errors_without_conversion() {
+    let resp = ServerError::status(StatusCode::NOT_FOUND).into_response();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp =
+        ServerError::other(format!("no user named {:?}", "alice")).to_response(EnvType::Local);
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(
+        body_to_string(resp)
+            .await
+            .contains(r#"no user named "alice""#)
+    );
+
+    // Into the errors of the other libraries
+    let err: Box<dyn std::error::Error + Send + Sync> =
+        ServerError::status(StatusCode::FORBIDDEN).into();
+    assert!(err.to_string().contains("403"));
+}
+
+#[test]
+fn different_error_ids() {
+    assert_ne!(ErrorId::generate(), ErrorId::generate());
+}
+
+
+#[derive(Debug)]
+struct UserNotFound(&'static str);
+
+impl Display for UserNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no user named {:?} in the database", self.0)
+    }
+}
+
+impl std::error::Error for UserNotFound {}
+
+impl HttpError for UserNotFound {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::NOT_FOUND
+    }
+
+    fn public_message(&self) -> Option<String> {
+        Some(format!("There is no user named {}", self.0))
+    }
+}
+
+fn find_user(name: &'static str) -> ServerResult<()> {
+    Err(UserNotFound(name))?
+}
+
+fn read_config() -> ServerResult<Vec<u8>> {
+    Ok(std::fs::read("/this/file/does/not/exist")?)
+}
+
+#[tokio::test]
+async fn user_errors_choose_their_status_and_message() {
+    for env_type in [EnvType::Local, EnvType::Prod] {
+        let resp = find_user("alice").unwrap_err().to_response(env_type);
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert!(resp.extensions().get::<ErrorId>().is_none());
+        assert_eq!(body_to_string(resp).await, "There is no user named alice");
+    }
+}
+
+#[tokio::test]
+async fn std_errors_are_internal_server_errors() {
+    for env_type in [
+        EnvType::Local,
+        EnvType::Dev,
+        EnvType::Staging,
+        EnvType::Prod,
+    ] {
+        let resp = read_config().unwrap_err().to_response(env_type);
+
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(resp.extensions().get::<ErrorId>().is_some());
+
+        let body = body_to_string(resp).await;
+        match env_type {
+            EnvType::Local | EnvType::Dev => assert!(body.contains("No such file or directory")),
+            EnvType::Prod | EnvType::Staging => {
+                assert!(!body.contains("No such file or directory"));
+                assert!(body.contains("An unexpected error occurred."));
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+struct QueryFailed(std::io::Error);
+
+impl Display for QueryFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the query failed")
+    }
+}
+
+impl std::error::Error for QueryFailed {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[tokio::test]
+async fn boxed_errors_keep_their_source_chain() {
+    let err: Box<dyn std::error::Error + Send + Sync> =
+        Box::new(QueryFailed(std::io::Error::other("connection reset")));
+    let resp = ServerError::other(err).to_response(EnvType::Dev);
+
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let body = body_to_string(resp).await;
+    assert!(body.contains("the query failed"));
+    assert!(body.contains("Caused by: connection reset"));
+}
@@ -0,0 +1,64 @@
+use {
+    PROJECT_NAME_TO_RESOLVE::shared::wini::fragment::{Fragment, extract},
+    hyper::{HeaderMap, header::HeaderValue},
+};
+
+fn headers(headers: &[(&'static str, &'static str)]) -> HeaderMap {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.parse().expect("valid header name"),
+                HeaderValue::from_static(value),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn fragment_requests() {
+    assert_eq!(Fragment::from_request(&headers(&[]), None), None);
+    assert_eq!(Fragment::from_request(&headers(&[]), Some("page=2")), None);
+    assert_eq!(
+        Fragment::from_request(&headers(&[("hx-request", "true")]), None),
+        Some(Fragment::Body)
+    );
+    assert_eq!(
+        Fragment::from_request(
+            &headers(&[("hx-request", "true"), ("hx-boosted", "true")]),
+            None
+        ),
+        None
+    );
+    assert_eq!(
+        Fragment::from_request(&headers(&[("x-wini-fragment", "")]), None),
+        Some(Fragment::Body)
+    );
+    assert_eq!(
+        Fragment::from_request(&headers(&[("x-wini-fragment", "comments")]), None),
+        Some(Fragment::Named("comments".to_owned()))
+    );
+}
+
+#[test]
+fn query_before_headers() {
+    assert_eq!(
+        Fragment::from_request(
+            &headers(&[("x-wini-fragment", "comments")]),
+            Some("page=2&fragment=related%20posts")
+        ),
+        Some(Fragment::Named("related posts".to_owned()))
+    );
+    assert_eq!(
+        Fragment::from_request(&headers(&[]), Some("fragment")),
+        Some(Fragment::Body)
+    );
+}
+
+#[test]
+fn extract_fragments() {
+    let html = "<h1>Post</h1><!--wini-fragment:comments--><ul></ul><!--/wini-fragment:comments-->";
+
+    assert_eq!(extract(html, "comments"), Some("<ul></ul>"));
+    assert_eq!(extract(html, "related"), None);
+}
@@ -0,0 +1,92 @@
+use {
+    PROJECT_NAME_TO_RESOLVE::shared::wini::fs_router::{Segment, module_path, pages_module, route},
+    build::PageModule,
+};
+
+/// `./build.rs`, that finds the pages with [`PageModule`]
+#[path = "../../build.rs"]
+#[allow(dead_code, reason = "Only the pages found are tested")]
+mod build;
+
+#[test]
+fn segments_from_dir_names() {
+    assert_eq!(Segment::from_dir_name("index"), Segment::Index);
+    assert_eq!(Segment::from_dir_name("about"), Segment::Static("about"));
+    assert_eq!(Segment::from_dir_name("[slug]"), Segment::Param("slug"));
+    assert_eq!(
+        Segment::from_dir_name("[...path]"),
+        Segment::CatchAll("path")
+    );
+}
+
+#[test]
+fn routes_of_pages() {
+    assert_eq!(route(&[]), "/");
+    assert_eq!(route(&["index"]), "/");
+    assert_eq!(route(&["about"]), "/about");
+    assert_eq!(route(&["blog", "index"]), "/blog");
+    assert_eq!(route(&["blog", "[slug]"]), "/blog/{slug}");
+    assert_eq!(
+        route(&["users", "[id]", "posts", "[post_id]"]),
+        "/users/{id}/posts/{post_id}"
+    );
+    assert_eq!(route(&["docs", "[...path]"]), "/docs/{*path}");
+}
+
+#[test]
+fn modules_of_pages() {
+    assert_eq!(pages_module("./pages"), "crate::pages");
+    assert_eq!(pages_module("./app/pages/"), "crate::app::pages");
+
+    assert_eq!(
+        module_path("crate::pages", &["index"]),
+        "crate::pages::index"
+    );
+    assert_eq!(
+        module_path("crate::pages", &["blog", "[slug]"]),
+        "crate::pages::blog::slug"
+    );
+    assert_eq!(
+        module_path("crate::pages", &["getting-started"]),
+        "crate::pages::getting_started"
+    );
+}
+
+#[test]
+fn page_modules() {
+    let file = std::env::temp_dir().join("wini-page-module.rs");
+    std::fs::write(
+        &file,
+        r##"
+#[page(path = "/users/{id}")]
+pub async fn user(id: u32) -> Markup {}
+
+#[wini_macros::page(title = "Search", path="/search", js_pkgs = ["htmx"])]
+pub async fn render() -> Markup {}
+
+/// #[page(path = "/in-a-comment")]
+pub async fn layout(s: Markup) -> Markup {}
+
+const NOT_A_PAGE: &str = r#"#[page(path = "/in-a-string")]"#;
+
+async fn action() {}
+
+mod nested {
+    #[page(title = "Nested", path = "/nested")]
+    pub async fn nested() -> Markup {}
+}
+"##,
+    )
+    .unwrap();
+
+    let module = PageModule::parse(&file);
+    assert!(module.has_page);
+    assert!(module.has_layout);
+    // Not public
+    assert!(!module.has_action);
+    assert!(!module.has_ssg_params);
+    assert_eq!(module.paths, ["/users/{id}", "/search", "/nested"]);
+
+    // Without a file
+    assert!(!PageModule::parse(&file.with_extension("none")).has_page);
+}
@@ -1 +1,21 @@
+mod base_path;
 mod canonicalize_relative_path;
+mod compile_fail;
+mod context;
+mod critical_css;
+mod csp;
+mod csrf;
+mod err;
+mod fragment;
+mod fs_router;
+mod navigation;
+mod problem_details;
+mod resource_hints;
+mod route;
+mod scoped_css;
+mod security_headers;
+mod session;
+// IFFEAT ssg
+mod ssg;
+// ENDIF
+mod startup;
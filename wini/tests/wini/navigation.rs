@@ -0,0 +1,107 @@
+use {
+    PROJECT_NAME_TO_RESOLVE::{
+        shared::wini::{config::ConfigNavigation, resource_hints::ResourceHints},
+        template::{
+            critical_css::StyleSheets,
+            html::html,
+            navigation::{inline_key, is_rerun, key},
+        },
+    },
+    maud::Markup,
+};
+
+fn enabled() -> ConfigNavigation {
+    toml::from_str("enabled = true").unwrap()
+}
+
+#[test]
+fn prefetch_by_default() {
+    let config = enabled();
+
+    assert!(*config.prefetch());
+}
+
+#[test]
+fn keys_only_if_enabled() {
+    let disabled: ConfigNavigation = toml::from_str("").unwrap();
+
+    assert_eq!(
+        key(&enabled(), "stylesheet", "/main.css").as_deref(),
+        Some("stylesheet:/main.css")
+    );
+    assert_eq!(key(&disabled, "stylesheet", "/main.css"), None);
+    assert_eq!(inline_key(&disabled, "style", "a{}"), None);
+}
+
+#[test]
+fn inline_keys_from_content() {
+    let config = enabled();
+
+    assert_eq!(
+        inline_key(&config, "style", "a{}"),
+        inline_key(&config, "style", "a{}")
+    );
+    assert_ne!(
+        inline_key(&config, "style", "a{}"),
+        inline_key(&config, "style", "b{}")
+    );
+    assert!(
+        inline_key(&config, "style", "a{}")
+            .unwrap()
+            .starts_with("style:")
+    );
+}
+
+#[test]
+fn rerun_scripts() {
+    let config = enabled();
+
+    assert!(is_rerun(&config, "/src/pages/index/rerun.js"));
+    assert!(is_rerun(&config, "/src/pages/index/chart.rerun.js"));
+    assert!(!is_rerun(&config, "/src/pages/index/script.js"));
+    assert!(!is_rerun(&config, "/src/pages/index/rerun.js.map"));
+    assert!(!is_rerun(
+        &toml::from_str("").unwrap(),
+        "/src/pages/index/rerun.js"
+    ));
+}
+
+#[test]
+fn attributes_of_the_template() {
+    let style_sheets = StyleSheets {
+        blocking: vec!["/src/pages/index/style.css".to_owned()],
+        ..Default::default()
+    };
+    let render = |config: &ConfigNavigation| {
+        html(
+            "<p>Hello</p>",
+            vec![
+                "/src/pages/index/script.js".to_owned(),
+                "/src/pages/index/rerun.js".to_owned(),
+            ],
+            &style_sheets,
+            &Markup::default(),
+            None,
+            &ResourceHints::default(),
+            None,
+            None,
+            config,
+        )
+    };
+
+    let page = render(&enabled());
+    assert!(page.contains(r#"<html lang="en" data-wini-navigation data-wini-prefetch>"#));
+    assert!(page.contains(
+        r#"<link rel="stylesheet" href="/src/pages/index/style.css" data-wini-key="stylesheet:/src/pages/index/style.css">"#
+    ));
+    assert!(page.contains(
+        r#"<script src="/src/pages/index/script.js" defer data-wini-key="script:/src/pages/index/script.js"></script>"#
+    ));
+    assert!(page.contains(
+        r#"<script src="/src/pages/index/rerun.js" defer data-wini-key="script:/src/pages/index/rerun.js" data-wini-rerun></script>"#
+    ));
+
+    let page = render(&toml::from_str("").unwrap());
+    assert!(page.contains(r#"<html lang="en">"#));
+    assert!(!page.contains("data-wini-"));
+}
@@ -0,0 +1,39 @@
+use {
+    PROJECT_NAME_TO_RESOLVE::shared::wini::problem_details::{ProblemDetails, prefers_json},
+    axum::response::IntoResponse,
+    hyper::{StatusCode, header::CONTENT_TYPE},
+};
+
+#[test]
+fn json_clients() {
+    assert!(prefers_json("application/json"));
+    assert!(prefers_json("application/problem+json"));
+    assert!(prefers_json("application/json, text/plain, */*"));
+    assert!(prefers_json("text/html;q=0.5, application/json"));
+}
+
+#[test]
+fn html_clients() {
+    assert!(!prefers_json(
+        "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"
+    ));
+    assert!(!prefers_json("*/*"));
+    assert!(!prefers_json(""));
+    assert!(!prefers_json("text/html, application/json"));
+    assert!(!prefers_json("application/json;q=0.2, text/*"));
+}
+
+#[test]
+fn problem_details_response() {
+    let resp = ProblemDetails {
+        detail: Some("There is no user named alice".to_owned()),
+        ..ProblemDetails::new(StatusCode::NOT_FOUND)
+    }
+    .into_response();
+
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        resp.headers().get(CONTENT_TYPE).unwrap(),
+        "application/problem+json"
+    );
+}
@@ -0,0 +1,33 @@
+use PROJECT_NAME_TO_RESOLVE::shared::wini::resource_hints::{ResourceHints, preload_destination};
+
+#[test]
+fn destinations() {
+    assert_eq!(preload_destination("/fonts/inter.woff2"), "font");
+    assert_eq!(preload_destination("/hero.WEBP?v=2"), "image");
+    assert_eq!(preload_destination("/main.css"), "style");
+    assert_eq!(preload_destination("/api/data"), "fetch");
+}
+
+#[test]
+fn preconnect_only_to_other_origins() {
+    let mut hints = ResourceHints::default();
+    hints.preconnect_to_origin_of("/src/pages/index/script.js");
+    hints.preconnect_to_origin_of("https://cdn.example.com/lib/leaflet.js");
+    hints.preconnect_to_origin_of("https://unpkg.com/htmx.org?v=2");
+    hints.preconnect_to_origin_of("//fonts.example.com/inter.css");
+    hints.preconnect_to_origin_of("/redirect?to=https://example.com");
+
+    assert_eq!(
+        hints.preconnects.into_iter().collect::<Vec<_>>(),
+        [
+            "//fonts.example.com",
+            "https://cdn.example.com",
+            "https://unpkg.com"
+        ]
+    );
+}
+
+#[test]
+fn no_link_header_without_hints() {
+    assert_eq!(ResourceHints::default().link_header(), None);
+}
@@ -0,0 +1,52 @@
+use PROJECT_NAME_TO_RESOLVE::shared::wini::route::{query_pair, url};
+
+#[test]
+fn url_with_params() {
+    assert_eq!(url("/", &[], &[]), "/");
+    assert_eq!(url("/users/{id}", &[("id", &42)], &[]), "/users/42");
+    assert_eq!(
+        url(
+            "/blog/{year}/{slug}",
+            &[("slug", &"hello-world"), ("year", &2025)],
+            &[]
+        ),
+        "/blog/2025/hello-world"
+    );
+}
+
+#[test]
+fn params_are_encoded() {
+    assert_eq!(
+        url("/blog/{slug}", &[("slug", &"a b/c?")], &[]),
+        "/blog/a%20b%2Fc%3F"
+    );
+    assert_eq!(
+        url("/docs/{*path}", &[("path", &"guide/getting started")], &[]),
+        "/docs/guide/getting%20started"
+    );
+}
+
+#[test]
+fn missing_param_is_kept() {
+    assert_eq!(url("/users/{id}", &[], &[]), "/users/{id}");
+}
+
+#[test]
+fn url_with_query() {
+    assert_eq!(
+        url(
+            "/search",
+            &[],
+            &[
+                query_pair("q", "rust & axum"),
+                query_pair("page", &None::<u32>),
+                query_pair("limit", &Some(10)),
+            ]
+        ),
+        "/search?q=rust+%26+axum&limit=10"
+    );
+    assert_eq!(
+        url("/search", &[], &[query_pair("page", &None::<u32>)]),
+        "/search"
+    );
+}
@@ -0,0 +1,100 @@
+use {
+    PROJECT_NAME_TO_RESOLVE::{
+        components::scoped_card::scoped_card,
+        shared::wini::{
+            CSS_FILES,
+            scoped_css::{is_scoped, scope_attribute, scope_css},
+        },
+    },
+    maud::html,
+};
+
+fn scoped(css: &str) -> String {
+    scope_css(css, "data-s").replace("[data-s]", "[S]")
+}
+
+#[test]
+fn scoped_file_names() {
+    assert!(is_scoped("/src/components/button/scoped.css"));
+    assert!(is_scoped("/src/components/button/button.scoped.css"));
+    assert!(!is_scoped("/src/components/button/style.css"));
+    assert!(!is_scoped("/src/components/scoped.css/style.css"));
+}
+
+#[test]
+fn same_attribute_with_or_without_leading_slash() {
+    let attribute = scope_attribute("src/components/button/scoped.css");
+
+    assert_eq!(
+        attribute,
+        scope_attribute("/src/components/button/scoped.css")
+    );
+    assert_ne!(attribute, scope_attribute("src/components/card/scoped.css"));
+    assert!(attribute.starts_with("data-wini-"));
+}
+
+#[test]
+fn scopes_the_last_compound_selector() {
+    assert_eq!(
+        scoped(".button { color: red }"),
+        ".button[S] { color: red }"
+    );
+    assert_eq!(
+        scoped(".card > .title:hover, a::before{}"),
+        ".card > .title:hover[S], a[S]::before{}"
+    );
+    assert_eq!(
+        scoped("li:not(.a, .b):after{}"),
+        "li:not(.a, .b)[S]:after{}"
+    );
+    assert_eq!(scoped(r#"a[href~="x y"]{}"#), r#"a[href~="x y"][S]{}"#);
+}
+
+#[test]
+fn global_selectors() {
+    assert_eq!(scoped(":global(.dark) .button{}"), ".dark .button[S]{}");
+    assert_eq!(scoped(":global(body > main){}"), "body > main{}");
+    assert_eq!(scoped(".a :global(.b)::after{}"), ".a .b::after{}");
+}
+
+#[test]
+fn at_rules() {
+    assert_eq!(
+        scoped("@media (min-width: 10px) { .a { b: c } }"),
+        "@media (min-width: 10px) { .a[S] { b: c } }"
+    );
+    assert_eq!(
+        scoped("@keyframes spin { from { a: b } to { a: c } } @import url(\"a.css\");"),
+        "@keyframes spin { from { a: b } to { a: c } } @import url(\"a.css\");"
+    );
+}
+
+#[test]
+fn keeps_comments_and_strings() {
+    assert_eq!(
+        scoped("/* .a, { */\n.b::after { content: \"}\" }"),
+        "/* .a, { */\n.b[S]::after { content: \"}\" }"
+    );
+}
+
+#[tokio::test]
+async fn scoped_component() {
+    let attribute = scope_attribute("src/components/scoped_card/scoped.css");
+
+    // Not on the button it renders, nor on the children it's given
+    assert_eq!(
+        scoped_card(html! { p.title { "Child" } })
+            .await
+            .into_string(),
+        format!(
+            r#"<article {attribute} class="card"><h2 {attribute} class="title">Scoped</h2><p class="title">Child</p><button>Welcome to Wini!</button></article>"#
+        )
+    );
+
+    // Compiled from `scoped.scss`
+    let css = CSS_FILES
+        .get("/src/components/scoped_card/scoped.css")
+        .unwrap();
+    assert!(css.contains(&format!(".card .title[{attribute}]")));
+    assert!(css.contains(&format!("button[{attribute}]")));
+}
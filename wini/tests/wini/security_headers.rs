@@ -0,0 +1,80 @@
+use {
+    PROJECT_NAME_TO_RESOLVE::shared::wini::{
+        config::{ConfigHeaders, PerEnvironment},
+        env::EnvType,
+        security_headers::SecurityHeaders,
+    },
+    axum::http::HeaderMap,
+};
+
+fn headers_from_toml(toml: &str, env: EnvType) -> SecurityHeaders {
+    let config: PerEnvironment<ConfigHeaders> = toml::from_str(toml).unwrap();
+    SecurityHeaders::from_config(&config, env).unwrap()
+}
+
+#[test]
+fn defaults_without_config() {
+    let security_headers = headers_from_toml("", EnvType::Prod);
+    let mut headers = HeaderMap::new();
+    security_headers.apply("/", &mut headers);
+
+    assert_eq!(headers.get("x-content-type-options").unwrap(), "nosniff");
+    assert_eq!(headers.get("x-frame-options").unwrap(), "SAMEORIGIN");
+    assert!(headers.contains_key("strict-transport-security"));
+    assert!(!headers.contains_key("cross-origin-embedder-policy"));
+}
+
+#[test]
+fn overwritten_per_environment() {
+    let toml = r#"
+        [default]
+        x_frame_options = "DENY"
+        referrer_policy = "no-referrer"
+        [local]
+        x_frame_options = ""
+    "#;
+
+    let mut headers = HeaderMap::new();
+    headers_from_toml(toml, EnvType::Prod).apply("/", &mut headers);
+    assert_eq!(headers.get("x-frame-options").unwrap(), "DENY");
+    assert_eq!(headers.get("referrer-policy").unwrap(), "no-referrer");
+
+    let mut headers = HeaderMap::new();
+    headers_from_toml(toml, EnvType::Local).apply("/", &mut headers);
+    assert!(!headers.contains_key("x-frame-options"));
+    assert_eq!(headers.get("referrer-policy").unwrap(), "no-referrer");
+}
+
+#[test]
+fn exempted_paths() {
+    let toml = r#"
+        [default.exemptions]
+        "/widget" = ["x-frame-options", "content-security-policy"]
+    "#;
+    let security_headers = headers_from_toml(toml, EnvType::Prod);
+
+    for path in ["/widget", "/widget/embed"] {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "content-security-policy",
+            "default-src 'self'".parse().unwrap(),
+        );
+        security_headers.apply(path, &mut headers);
+
+        assert!(!headers.contains_key("x-frame-options"));
+        assert!(!headers.contains_key("content-security-policy"));
+        assert!(headers.contains_key("x-content-type-options"));
+    }
+
+    let mut headers = HeaderMap::new();
+    security_headers.apply("/widgets", &mut headers);
+    assert!(headers.contains_key("x-frame-options"));
+}
+
+#[test]
+fn invalid_header_value() {
+    let config: PerEnvironment<ConfigHeaders> =
+        toml::from_str("[default]\nreferrer_policy = \"no\\nreferrer\"").unwrap();
+
+    assert!(SecurityHeaders::from_config(&config, EnvType::Prod).is_err());
+}
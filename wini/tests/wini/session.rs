@@ -0,0 +1,157 @@
+use PROJECT_NAME_TO_RESOLVE::shared::wini::session::{
+    FileStore,
+    MemoryStore,
+    Session,
+    SessionData,
+    SessionStore,
+    sign,
+    verify,
+};
+
+/// The id of a session from its `Set-Cookie`
+fn id_of(set_cookie: &str) -> String {
+    let value = set_cookie
+        .split(';')
+        .next()
+        .and_then(|cookie| cookie.strip_prefix("wini_session="))
+        .unwrap();
+
+    verify(value).unwrap().to_owned()
+}
+
+#[test]
+fn signed_ids() {
+    let cookie = sign("abc");
+
+    assert_eq!(verify(&cookie), Some("abc"));
+    assert_eq!(verify(&cookie.replacen("abc", "abd", 1)), None);
+    assert_eq!(verify("abc"), None);
+    assert_eq!(verify("abc.invalid"), None);
+}
+
+#[test]
+fn values_of_a_session() {
+    let session = Session::default();
+
+    session.insert("visits", 3).unwrap();
+    assert_eq!(session.get::<u32>("visits"), Some(3));
+    assert_eq!(session.get::<String>("visits"), None);
+
+    session.remove("visits");
+    assert_eq!(session.get::<u32>("visits"), None);
+}
+
+#[test]
+fn flashes_are_read_once() {
+    let session = Session::default();
+
+    session.flash("Saved!").unwrap();
+    session.flash("Published!").unwrap();
+
+    assert_eq!(session.take_flashes(), ["Saved!", "Published!"]);
+    assert!(session.take_flashes().is_empty());
+}
+
+#[tokio::test]
+async fn saved_only_if_changed() {
+    let store = MemoryStore::new();
+
+    let session = Session::default();
+    assert_eq!(session.save(&store).await.unwrap(), None);
+
+    session.insert("user", "wini").unwrap();
+    let set_cookie = session.save(&store).await.unwrap().unwrap();
+    assert!(set_cookie.contains("HttpOnly"));
+    let id = id_of(&set_cookie);
+
+    let data = store.load(&id).await.unwrap().unwrap();
+    assert_eq!(data.get("user").unwrap(), "wini");
+
+    // Not saved again without changes
+    assert_eq!(session.save(&store).await.unwrap(), None);
+
+    // Removed with its cookie once it's empty
+    session.clear();
+    let set_cookie = session.save(&store).await.unwrap().unwrap();
+    assert!(set_cookie.starts_with("wini_session=;"));
+    assert!(set_cookie.contains("Max-Age=0"));
+    assert_eq!(store.load(&id).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn renewed_ids() {
+    let store = MemoryStore::new();
+
+    let session = Session::new(Some("old".to_owned()), SessionData::new());
+    store
+        .save(
+            "old",
+            &SessionData::new(),
+            std::time::Duration::from_secs(60),
+        )
+        .await
+        .unwrap();
+
+    session.insert("user", "wini").unwrap();
+    session.renew();
+    let id = id_of(&session.save(&store).await.unwrap().unwrap());
+
+    assert_ne!(id, "old");
+    assert_eq!(store.load("old").await.unwrap(), None);
+    assert!(store.load(&id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn file_store() {
+    let dir = std::env::temp_dir().join(format!("wini-sessions-{}", std::process::id()));
+    let store = FileStore::new(&dir);
+
+    let mut data = SessionData::new();
+    data.insert("user".to_owned(), "wini".into());
+
+    store
+        .save("abc", &data, std::time::Duration::from_secs(60))
+        .await
+        .unwrap();
+    assert_eq!(store.load("abc").await.unwrap(), Some(data.clone()));
+
+    // Expired
+    store
+        .save("abc", &data, std::time::Duration::ZERO)
+        .await
+        .unwrap();
+    assert_eq!(store.load("abc").await.unwrap(), None);
+
+    store.delete("abc").await.unwrap();
+    assert_eq!(store.load("unknown").await.unwrap(), None);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn expired_files_purged() {
+    let dir = std::env::temp_dir().join(format!("wini-sessions-purge-{}", std::process::id()));
+    let store = FileStore::new(&dir);
+
+    // Nothing to purge before the first session
+    store.purge_expired().await.unwrap();
+
+    let data = SessionData::new();
+    store
+        .save("expired", &data, std::time::Duration::ZERO)
+        .await
+        .unwrap();
+    store
+        .save("valid", &data, std::time::Duration::from_secs(60))
+        .await
+        .unwrap();
+    std::fs::write(dir.join("other.txt"), "").unwrap();
+
+    store.purge_expired().await.unwrap();
+
+    assert!(!dir.join("expired.json").exists());
+    assert!(dir.join("valid.json").exists());
+    assert!(dir.join("other.txt").exists());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,12 @@
+use PROJECT_NAME_TO_RESOLVE::shared::wini::ssg::strip_nonces;
+
+#[test]
+fn nonces_are_removed() {
+    assert_eq!(
+        strip_nonces(
+            r#"<link rel="stylesheet" href="/main.css" nonce="a+b/c=="><script nonce="abc">1</script>"#
+        ),
+        r#"<link rel="stylesheet" href="/main.css"><script>1</script>"#
+    );
+    assert_eq!(strip_nonces("<p>nonce=</p>"), "<p>nonce=</p>");
+}
@@ -0,0 +1,80 @@
+use {
+    PROJECT_NAME_TO_RESOLVE::shared::wini::{
+        error_page::{ErrorPageFuture, ErrorPages, RegisteredErrorPage},
+        startup::{StartupProblem, StartupReport, line_and_column, validate},
+    },
+    hyper::StatusCode,
+    maud::html,
+};
+
+#[test]
+fn project_is_valid() {
+    let report = validate();
+
+    assert!(report.problems.is_empty(), "{report}");
+}
+
+#[test]
+fn position_of_an_offset() {
+    let content = "[path]\npages = 1\népingle = 2";
+
+    assert_eq!(line_and_column(content, 0), (1, 1));
+    assert_eq!(line_and_column(content, 15), (2, 9));
+    assert_eq!(line_and_column(content, content.len()), (3, 12));
+}
+
+#[test]
+fn report_lists_all_the_problems() {
+    let report = StartupReport {
+        problems: vec![
+            StartupProblem {
+                location: ".env".to_owned(),
+                message: "`PORT` isn't defined".to_owned(),
+            },
+            StartupProblem {
+                location: "./wini.toml:3:1".to_owned(),
+                message: "missing field `cache`\nin `path`".to_owned(),
+            },
+        ],
+    };
+
+    assert_eq!(
+        report.to_string(),
+        "The server can't start: 2 problems found\n\n  \
+         .env\n    `PORT` isn't defined\n\n  \
+         ./wini.toml:3:1\n    missing field `cache`\n    in `path`"
+    );
+}
+
+#[test]
+fn duplicate_error_pages() {
+    let page = |name| {
+        RegisteredErrorPage {
+            page: |_, _| -> ErrorPageFuture {
+                Box::pin(async {
+                    html! {}
+                })
+            },
+            name,
+            file: "src/pages/error/mod.rs",
+        }
+    };
+
+    let mut error_pages = ErrorPages::new();
+    error_pages.register(Some(404), page("error::not_found"));
+    error_pages.register(None, page("error::error"));
+    assert!(error_pages.problems().is_empty());
+
+    error_pages.register(Some(404), page("error::other"));
+    error_pages.register(Some(500), page("error::internal"));
+    assert_eq!(
+        error_pages.problems(),
+        vec![(
+            "src/pages/error/mod.rs",
+            "Multiple error pages registered for 404: `error::not_found` and `error::other`"
+                .to_owned()
+        )]
+    );
+    // The first one is kept
+    assert!(error_pages.get(StatusCode::NOT_FOUND).is_some());
+}